@@ -1,19 +1,21 @@
 #![allow(clippy::redundant_closure)]
 
 use futures::executor::block_on;
+use futures::TryStreamExt;
 
 use serde::{Deserialize, Serialize};
 
 use rusoto_core::Region;
-use rusoto_dynamodb::{DynamoDb, DynamoDbClient, PutItemInput, QueryInput};
+use rusoto_dynamodb::DynamoDbClient;
 
-use serde_dynamodb::ToQueryInput;
-use serde_dynamodb_derive::ToQueryInput;
+use serde_dynamodb::{Paginator, Table, ToQueryInput};
+use serde_dynamodb_derive::{DynamoItem, ToQueryInput};
 
-#[derive(Serialize, Deserialize, ToQueryInput)]
+#[derive(Serialize, Deserialize, ToQueryInput, DynamoItem)]
+#[dynamodb(table = "tasks")]
 struct Task {
+    #[dynamodb(partition_key)]
     id: String,
-    #[serde(rename = "mystatus")]
     status: Option<String>,
 }
 
@@ -25,23 +27,20 @@ fn main() {
         status: Some(String::from("some status")),
     };
 
-    let _query_params = PutItemInput {
-        table_name: String::from("TableName"),
-        item: serde_dynamodb::to_hashmap(&task).unwrap(),
-        ..Default::default()
-    };
+    let tasks = Table::<Task, _>::new(&client);
+    block_on(tasks.put(&task)).unwrap();
 
     let task_query_input = TaskQueryInput {
         id: Some("Entry Id".to_string()),
         ..Default::default()
     };
 
-    let _my_tasks: Vec<Task> =
-        block_on(client.query(task_query_input.to_query_input(String::from("tableName"))))
-            .unwrap()
-            .items
-            .unwrap_or_else(|| vec![])
-            .into_iter()
-            .map(|item| serde_dynamodb::from_hashmap(item).unwrap())
-            .collect();
+    let my_tasks: Result<Vec<Task>, _> = block_on(
+        Paginator::query(
+            &client,
+            task_query_input.to_query_input(String::from("tasks")),
+        )
+        .try_collect(),
+    );
+    let _my_tasks = my_tasks.unwrap();
 }
@@ -0,0 +1,546 @@
+//! Parse the `#[serde(...)]` and `#[dynamodb(...)]` attributes of a struct.
+
+use syn::punctuated::Punctuated;
+use syn::{
+    Data, DeriveInput, Expr, ExprLit, Fields, Ident, Lit, LitInt, LitStr, Meta, Token, Type,
+};
+
+/// A field of the struct.
+pub(crate) struct Field {
+    /// The name of the field in Rust.
+    pub ident: Ident,
+    /// The type of the field.
+    pub ty: Type,
+    /// The name of the attribute in DynamoDB, after serde renames.
+    pub name: String,
+    /// Whether the field is skipped by serde when serializing.
+    pub skipped: bool,
+    /// Whether the field is the partition key of the table.
+    pub partition_key: bool,
+    /// Whether the field is the sort key of the table.
+    pub sort_key: bool,
+    /// Whether the field is the time to live attribute.
+    pub ttl: bool,
+    /// Whether the field is the version of the item, for optimistic locking.
+    pub version: bool,
+    /// Whether the field is set to the time the item was created.
+    pub created_at: bool,
+    /// Whether the field is set to the time the item was last written.
+    pub updated_at: bool,
+    /// The module from `#[serde(with = "...")]`.
+    pub with: Option<syn::Path>,
+    /// The function from `#[serde(serialize_with = "...")]`.
+    pub serialize_with: Option<syn::Path>,
+    /// The function from `#[serde(deserialize_with = "...")]`.
+    pub deserialize_with: Option<syn::Path>,
+}
+
+/// The attributes copied to a secondary index.
+pub(crate) enum Projection {
+    All,
+    KeysOnly,
+    /// The positions of the fields copied to the index, besides the keys.
+    Include(Vec<usize>),
+}
+
+/// A secondary index of the table, from `#[dynamodb(index = "...", partition_key = "...")]`
+/// or `#[dynamodb(local_index = "...", sort_key = "...")]`.
+pub(crate) struct Index {
+    /// The name of the index.
+    pub name: String,
+    /// Whether this is a local secondary index.
+    pub local: bool,
+    /// The position of the partition key of the index in the fields.
+    pub partition_key: usize,
+    /// The position of the sort key of the index in the fields.
+    pub sort_key: Option<usize>,
+    /// The attributes copied to the index.
+    pub projection: Projection,
+}
+
+/// A struct with its DynamoDB related attributes.
+pub(crate) struct Container {
+    /// The name of the struct.
+    pub ident: Ident,
+    /// The visibility of the struct.
+    pub vis: syn::Visibility,
+    /// The fields of the struct.
+    pub fields: Vec<Field>,
+    /// The name of the table, from `#[dynamodb(table = "...")]`.
+    pub table: Option<String>,
+    /// The secondary indexes of the table.
+    pub indexes: Vec<Index>,
+    /// The `StreamViewType` of the stream of the table, from `#[dynamodb(stream = "...")]`.
+    pub stream: Option<String>,
+    /// The read and write capacity units of the table, from
+    /// `#[dynamodb(read_capacity = ..., write_capacity = ...)]`.
+    pub capacity: Option<(i64, i64)>,
+}
+
+impl Container {
+    /// The position of the partition key of the table in the fields.
+    pub fn partition_key(&self) -> Option<usize> {
+        self.fields.iter().position(|field| field.partition_key)
+    }
+
+    /// The position of the sort key of the table in the fields.
+    pub fn sort_key(&self) -> Option<usize> {
+        self.fields.iter().position(|field| field.sort_key)
+    }
+
+    pub fn from_derive_input(input: &DeriveInput) -> syn::Result<Self> {
+        let named = match &input.data {
+            Data::Struct(data) => match &data.fields {
+                Fields::Named(named) => named,
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        input,
+                        "only structs with named fields are supported",
+                    ))
+                }
+            },
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    input,
+                    "only structs with named fields are supported",
+                ))
+            }
+        };
+
+        let rename_all = serde_rename_all(&input.attrs)?;
+        let mut fields = vec![];
+        for field in &named.named {
+            let ident = field.ident.clone().expect("named fields have a name");
+            let serde = serde_field(&field.attrs)?;
+            let mut parsed = Field {
+                name: serde
+                    .rename
+                    .unwrap_or_else(|| rename_field(&ident.to_string(), rename_all.as_deref())),
+                ident,
+                ty: field.ty.clone(),
+                skipped: serde.skipped,
+                partition_key: false,
+                sort_key: false,
+                ttl: false,
+                version: false,
+                created_at: false,
+                updated_at: false,
+                with: serde.with,
+                serialize_with: serde.serialize_with,
+                deserialize_with: serde.deserialize_with,
+            };
+            for attr in field
+                .attrs
+                .iter()
+                .filter(|attr| attr.path().is_ident("dynamodb"))
+            {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("partition_key") {
+                        parsed.partition_key = true;
+                        Ok(())
+                    } else if meta.path.is_ident("sort_key") {
+                        parsed.sort_key = true;
+                        Ok(())
+                    } else if meta.path.is_ident("ttl") {
+                        parsed.ttl = true;
+                        Ok(())
+                    } else if meta.path.is_ident("version") {
+                        parsed.version = true;
+                        Ok(())
+                    } else if meta.path.is_ident("created_at") {
+                        parsed.created_at = true;
+                        Ok(())
+                    } else if meta.path.is_ident("updated_at") {
+                        parsed.updated_at = true;
+                        Ok(())
+                    } else {
+                        Err(meta.error("unknown dynamodb field attribute"))
+                    }
+                })?;
+            }
+            if parsed.partition_key && parsed.sort_key {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    "a field can't be both the partition key and the sort key",
+                ));
+            }
+            if parsed.version && (parsed.partition_key || parsed.sort_key) {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    "a key field can't be the version",
+                ));
+            }
+            if parsed.created_at && parsed.updated_at {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    "a field can't be both the creation and the update time",
+                ));
+            }
+            if (parsed.created_at || parsed.updated_at) && (parsed.partition_key || parsed.sort_key)
+            {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    "a key field can't be a creation or update time",
+                ));
+            }
+            if parsed.created_at && !is_option(&parsed.ty) {
+                return Err(syn::Error::new_spanned(
+                    &field.ty,
+                    "a creation time must be an Option, as it is only set when it is None",
+                ));
+            }
+            if parsed.ttl
+                && parsed.with.is_none()
+                && parsed.serialize_with.is_none()
+                && is_time(&parsed.ty)
+            {
+                return Err(syn::Error::new_spanned(
+                    &field.ty,
+                    "a time to live must be a number of seconds since the epoch: add \
+                     #[serde(with = \"serde_dynamodb::timestamp::epoch_seconds\")]",
+                ));
+            }
+            fields.push(parsed);
+        }
+
+        if fields.iter().filter(|field| field.partition_key).count() > 1 {
+            return Err(syn::Error::new_spanned(
+                input,
+                "only one field can be the partition key",
+            ));
+        }
+        if fields.iter().filter(|field| field.sort_key).count() > 1 {
+            return Err(syn::Error::new_spanned(
+                input,
+                "only one field can be the sort key",
+            ));
+        }
+        if fields.iter().any(|field| field.sort_key)
+            && !fields.iter().any(|field| field.partition_key)
+        {
+            return Err(syn::Error::new_spanned(
+                input,
+                "a sort key needs a partition key",
+            ));
+        }
+
+        if fields.iter().filter(|field| field.ttl).count() > 1 {
+            return Err(syn::Error::new_spanned(
+                input,
+                "only one field can be the time to live",
+            ));
+        }
+        if fields.iter().filter(|field| field.version).count() > 1 {
+            return Err(syn::Error::new_spanned(
+                input,
+                "only one field can be the version",
+            ));
+        }
+        if fields.iter().filter(|field| field.created_at).count() > 1 {
+            return Err(syn::Error::new_spanned(
+                input,
+                "only one field can be the creation time",
+            ));
+        }
+        if fields.iter().filter(|field| field.updated_at).count() > 1 {
+            return Err(syn::Error::new_spanned(
+                input,
+                "only one field can be the update time",
+            ));
+        }
+
+        let mut table = None;
+        let mut indexes = vec![];
+        let mut stream = None;
+        let mut read_capacity = None;
+        let mut write_capacity = None;
+        for attr in input
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("dynamodb"))
+        {
+            let mut name = None;
+            let mut local = false;
+            let mut partition_key = None;
+            let mut sort_key = None;
+            let mut projection = None;
+            attr.parse_nested_meta(|meta| {
+                let field = |value: &LitStr| {
+                    fields
+                        .iter()
+                        .position(|field| field.ident == value.value())
+                        .ok_or_else(|| syn::Error::new_spanned(value, "unknown field"))
+                };
+                if meta.path.is_ident("read_capacity") {
+                    read_capacity = Some(meta.value()?.parse::<LitInt>()?.base10_parse()?);
+                    return Ok(());
+                } else if meta.path.is_ident("write_capacity") {
+                    write_capacity = Some(meta.value()?.parse::<LitInt>()?.base10_parse()?);
+                    return Ok(());
+                }
+                let value: LitStr = meta.value()?.parse()?;
+                if meta.path.is_ident("table") {
+                    table = Some(value.value());
+                } else if meta.path.is_ident("stream") {
+                    match value.value().as_str() {
+                        "KEYS_ONLY" | "NEW_IMAGE" | "OLD_IMAGE" | "NEW_AND_OLD_IMAGES" => {
+                            stream = Some(value.value())
+                        }
+                        _ => {
+                            return Err(syn::Error::new_spanned(value, "unknown stream view type"))
+                        }
+                    }
+                } else if meta.path.is_ident("index") {
+                    name = Some(value.value());
+                } else if meta.path.is_ident("local_index") {
+                    name = Some(value.value());
+                    local = true;
+                } else if meta.path.is_ident("partition_key") {
+                    partition_key = Some(field(&value)?);
+                } else if meta.path.is_ident("sort_key") {
+                    sort_key = Some(field(&value)?);
+                } else if meta.path.is_ident("projection") {
+                    projection = Some(match value.value().as_str() {
+                        "ALL" => Projection::All,
+                        "KEYS_ONLY" => Projection::KeysOnly,
+                        _ => return Err(syn::Error::new_spanned(value, "unknown projection")),
+                    });
+                } else if meta.path.is_ident("include") {
+                    let included = value
+                        .value()
+                        .split(',')
+                        .map(|name| {
+                            fields
+                                .iter()
+                                .position(|field| field.ident == name.trim())
+                                .ok_or_else(|| syn::Error::new_spanned(&value, "unknown field"))
+                        })
+                        .collect::<syn::Result<_>>()?;
+                    projection = Some(Projection::Include(included));
+                } else {
+                    return Err(meta.error("unknown dynamodb attribute"));
+                }
+                Ok(())
+            })?;
+
+            let projection = projection.unwrap_or(Projection::All);
+            match (name, local, partition_key, sort_key) {
+                (None, _, None, None) => (),
+                (Some(name), false, Some(partition_key), sort_key) => indexes.push(Index {
+                    name,
+                    local,
+                    partition_key,
+                    sort_key,
+                    projection,
+                }),
+                (Some(name), true, None, Some(sort_key)) => indexes.push(Index {
+                    name,
+                    local,
+                    partition_key: fields
+                        .iter()
+                        .position(|field| field.partition_key)
+                        .ok_or_else(|| {
+                            syn::Error::new_spanned(attr, "a local index needs a partition key")
+                        })?,
+                    sort_key: Some(sort_key),
+                    projection,
+                }),
+                (_, true, _, _) => {
+                    return Err(syn::Error::new_spanned(
+                        attr,
+                        "a local index needs a name and a sort key",
+                    ))
+                }
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        attr,
+                        "an index needs a name and a partition key",
+                    ))
+                }
+            }
+        }
+
+        let capacity = match (read_capacity, write_capacity) {
+            (Some(read), Some(write)) => Some((read, write)),
+            (None, None) => None,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    input,
+                    "read_capacity and write_capacity go together",
+                ))
+            }
+        };
+
+        Ok(Container {
+            ident: input.ident.clone(),
+            vis: input.vis.clone(),
+            fields,
+            table,
+            indexes,
+            stream,
+            capacity,
+        })
+    }
+}
+
+fn serde_metas(attrs: &[syn::Attribute]) -> syn::Result<Vec<Meta>> {
+    let mut metas = vec![];
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+        metas.extend(attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?);
+    }
+    Ok(metas)
+}
+
+fn string_value(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(value),
+            ..
+        }) => Some(value.value()),
+        _ => None,
+    }
+}
+
+/// The name given to attributes by `#[serde(rename = "...")]` or
+/// `#[serde(rename(serialize = "..."))]`.
+fn rename(meta: &Meta, key: &str) -> syn::Result<Option<String>> {
+    if !meta.path().is_ident(key) {
+        return Ok(None);
+    }
+    match meta {
+        Meta::NameValue(name_value) => Ok(string_value(&name_value.value)),
+        Meta::List(list) => {
+            let nested = list.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+            Ok(nested.iter().find_map(|meta| match meta {
+                Meta::NameValue(name_value) if name_value.path.is_ident("serialize") => {
+                    string_value(&name_value.value)
+                }
+                _ => None,
+            }))
+        }
+        Meta::Path(_) => Ok(None),
+    }
+}
+
+/// Whether `ty` is an `Option`.
+pub(crate) fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => {
+            matches!(path.path.segments.last(), Some(segment) if segment.ident == "Option")
+        }
+        _ => false,
+    }
+}
+
+/// Whether `ty` is a `SystemTime` or a `Duration`, maybe in an `Option`, that serde
+/// doesn't serialize as a number.
+fn is_time(ty: &Type) -> bool {
+    let segment = match ty {
+        Type::Path(path) => match path.path.segments.last() {
+            Some(segment) => segment,
+            None => return false,
+        },
+        _ => return false,
+    };
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(arguments) if segment.ident == "Option" => {
+            arguments.args.iter().any(|argument| match argument {
+                syn::GenericArgument::Type(ty) => is_time(ty),
+                _ => false,
+            })
+        }
+        _ => segment.ident == "SystemTime" || segment.ident == "Duration",
+    }
+}
+
+/// The path in `#[serde(with = "...")]`, `#[serde(serialize_with = "...")]` or
+/// `#[serde(deserialize_with = "...")]`.
+fn serde_path(meta: &Meta, key: &str) -> syn::Result<Option<syn::Path>> {
+    match meta {
+        Meta::NameValue(name_value) if name_value.path.is_ident(key) => {
+            match string_value(&name_value.value) {
+                Some(path) => syn::parse_str(&path).map(Some),
+                None => Ok(None),
+            }
+        }
+        _ => Ok(None),
+    }
+}
+
+struct SerdeField {
+    rename: Option<String>,
+    skipped: bool,
+    with: Option<syn::Path>,
+    serialize_with: Option<syn::Path>,
+    deserialize_with: Option<syn::Path>,
+}
+
+fn serde_field(attrs: &[syn::Attribute]) -> syn::Result<SerdeField> {
+    let mut field = SerdeField {
+        rename: None,
+        skipped: false,
+        with: None,
+        serialize_with: None,
+        deserialize_with: None,
+    };
+    for meta in serde_metas(attrs)? {
+        if let Some(name) = rename(&meta, "rename")? {
+            field.rename = Some(name);
+        }
+        if let Some(path) = serde_path(&meta, "with")? {
+            field.with = Some(path);
+        }
+        if let Some(path) = serde_path(&meta, "serialize_with")? {
+            field.serialize_with = Some(path);
+        }
+        if let Some(path) = serde_path(&meta, "deserialize_with")? {
+            field.deserialize_with = Some(path);
+        }
+        if meta.path().is_ident("skip") || meta.path().is_ident("skip_serializing") {
+            field.skipped = true;
+        }
+    }
+    Ok(field)
+}
+
+fn serde_rename_all(attrs: &[syn::Attribute]) -> syn::Result<Option<String>> {
+    let mut rule = None;
+    for meta in serde_metas(attrs)? {
+        if let Some(value) = rename(&meta, "rename_all")? {
+            rule = Some(value);
+        }
+    }
+    Ok(rule)
+}
+
+/// Rename a snake case field following a `#[serde(rename_all = "...")]` rule.
+fn rename_field(field: &str, rule: Option<&str>) -> String {
+    let pascal_case = || {
+        field
+            .split('_')
+            .map(|word| {
+                let mut chars = word.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                    .unwrap_or_default()
+            })
+            .collect::<String>()
+    };
+    match rule {
+        Some("lowercase") => field.to_lowercase(),
+        Some("UPPERCASE") | Some("SCREAMING_SNAKE_CASE") => field.to_uppercase(),
+        Some("PascalCase") => pascal_case(),
+        Some("camelCase") => {
+            let pascal = pascal_case();
+            let mut chars = pascal.chars();
+            chars
+                .next()
+                .map(|first| first.to_lowercase().chain(chars).collect())
+                .unwrap_or_default()
+        }
+        Some("kebab-case") => field.replace('_', "-"),
+        Some("SCREAMING-KEBAB-CASE") => field.replace('_', "-").to_uppercase(),
+        _ => field.to_owned(),
+    }
+}
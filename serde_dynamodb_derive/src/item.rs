@@ -0,0 +1,295 @@
+//! `#[derive(DynamoItem)]`
+
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+
+use crate::attributes::{Container, Field, Projection};
+
+pub(crate) fn expand(container: &Container) -> syn::Result<TokenStream> {
+    let ident = &container.ident;
+    let table = container.table.as_ref().ok_or_else(|| {
+        syn::Error::new_spanned(ident, "DynamoItem needs #[dynamodb(table = \"...\")]")
+    })?;
+    let partition_key = container.partition_key().ok_or_else(|| {
+        syn::Error::new_spanned(ident, "DynamoItem needs a #[dynamodb(partition_key)] field")
+    })?;
+    let key_fields: Vec<_> = std::iter::once(partition_key)
+        .chain(container.sort_key())
+        .collect();
+
+    let key_ident = format_ident!("{}Key", ident);
+    let key_doc = format!("The key of a [`{}`].", ident);
+    let partition_key_name = &container.fields[partition_key].name;
+    let sort_key_name = match container.sort_key() {
+        Some(sort_key) => {
+            let name = &container.fields[sort_key].name;
+            quote!(Some(#name))
+        }
+        None => quote!(None),
+    };
+
+    let indexes = container.indexes.iter().map(|index| {
+        let name = &index.name;
+        let local = index.local;
+        let partition_key = &container.fields[index.partition_key].name;
+        let sort_key = match index.sort_key {
+            Some(sort_key) => {
+                let name = &container.fields[sort_key].name;
+                quote!(Some(#name))
+            }
+            None => quote!(None),
+        };
+        let projection = match &index.projection {
+            Projection::All => quote!(::serde_dynamodb::IndexProjection::All),
+            Projection::KeysOnly => quote!(::serde_dynamodb::IndexProjection::KeysOnly),
+            Projection::Include(included) => {
+                let names = included.iter().map(|i| &container.fields[*i].name);
+                quote!(::serde_dynamodb::IndexProjection::Include(&[#(#names),*]))
+            }
+        };
+        quote! {
+            ::serde_dynamodb::SecondaryIndex {
+                name: #name,
+                local: #local,
+                partition_key: #partition_key,
+                sort_key: #sort_key,
+                projection: #projection,
+            }
+        }
+    });
+    let billing_mode = match container.capacity {
+        Some((read_capacity_units, write_capacity_units)) => quote! {
+            ::serde_dynamodb::BillingMode::Provisioned {
+                read_capacity_units: #read_capacity_units,
+                write_capacity_units: #write_capacity_units,
+            }
+        },
+        None => quote!(::serde_dynamodb::BillingMode::PayPerRequest),
+    };
+    let stream = match &container.stream {
+        Some(stream) => quote!(Some(#stream)),
+        None => quote!(None),
+    };
+    let ttl = match container.fields.iter().find(|field| field.ttl) {
+        Some(field) => {
+            let name = &field.name;
+            quote!(Some(#name))
+        }
+        None => quote!(None),
+    };
+    let version = match container.fields.iter().find(|field| field.version) {
+        Some(field) => {
+            let name = &field.name;
+            quote!(Some(#name))
+        }
+        None => quote!(None),
+    };
+
+    let created_at = match container.fields.iter().find(|field| field.created_at) {
+        Some(field) => {
+            let name = &field.name;
+            quote!(Some(#name))
+        }
+        None => quote!(None),
+    };
+    let updated_at = match container.fields.iter().find(|field| field.updated_at) {
+        Some(field) => {
+            let name = &field.name;
+            quote!(Some(#name))
+        }
+        None => quote!(None),
+    };
+    let timestamps = container
+        .fields
+        .iter()
+        .filter(|field| field.created_at || field.updated_at)
+        .map(|field| {
+            let name = &field.name;
+            let ty = &field.ty;
+            let serialize = serialize_function(field);
+            quote! {
+                #name => {
+                    struct Timestamp(#ty);
+                    impl ::serde::Serialize for Timestamp {
+                        fn serialize<S: ::serde::Serializer>(
+                            &self,
+                            serializer: S,
+                        ) -> ::std::result::Result<S::Ok, S::Error> {
+                            #serialize(&self.0, serializer)
+                        }
+                    }
+                    ::serde_dynamodb::to_attribute_value(&Timestamp(
+                        <#ty as ::serde_dynamodb::timestamp::Timestamp>::now(),
+                    ))
+                }
+            }
+        });
+
+    let item_key_struct = key_struct(container, &key_ident, &key_doc, &key_fields);
+    let index_key_structs = container.indexes.iter().map(|index| {
+        let mut fields = vec![index.partition_key];
+        fields.extend(index.sort_key);
+        for key in key_fields.iter() {
+            if !fields.contains(key) {
+                fields.push(*key);
+            }
+        }
+        let index_key_ident = format_ident!("{}{}Key", ident, camel_case(&index.name));
+        let index_key_doc = format!(
+            "The key of a [`{}`] in the `{}` index, with the key of the table.",
+            ident, index.name
+        );
+        key_struct(container, &index_key_ident, &index_key_doc, &fields)
+    });
+    let key_names = key_fields.iter().map(|i| &container.fields[*i].name);
+    let key_values = key_fields.iter().map(|i| {
+        let field = &container.fields[*i];
+        let ident = &field.ident;
+        let ty = &field.ty;
+        let serialize = serialize_function(field);
+        quote! {
+            {
+                struct Key<'a>(&'a #ty);
+                impl<'a> ::serde::Serialize for Key<'a> {
+                    fn serialize<S: ::serde::Serializer>(
+                        &self,
+                        serializer: S,
+                    ) -> ::std::result::Result<S::Ok, S::Error> {
+                        #serialize(self.0, serializer)
+                    }
+                }
+                ::serde_dynamodb::to_attribute_value(&Key(&self.#ident))?
+            }
+        }
+    });
+
+    Ok(quote! {
+        #item_key_struct
+
+        #(#index_key_structs)*
+
+        impl ::serde_dynamodb::DynamoItem for #ident {
+            const TABLE_NAME: &'static str = #table;
+            const PARTITION_KEY: &'static str = #partition_key_name;
+            const SORT_KEY: Option<&'static str> = #sort_key_name;
+            const INDEXES: &'static [::serde_dynamodb::SecondaryIndex] = &[#(#indexes),*];
+            const BILLING_MODE: ::serde_dynamodb::BillingMode = #billing_mode;
+            const STREAM_VIEW_TYPE: Option<&'static str> = #stream;
+            const TTL_ATTRIBUTE: Option<&'static str> = #ttl;
+            const VERSION_ATTRIBUTE: Option<&'static str> = #version;
+            const CREATED_AT_ATTRIBUTE: Option<&'static str> = #created_at;
+            const UPDATED_AT_ATTRIBUTE: Option<&'static str> = #updated_at;
+            type Key = #key_ident;
+
+            fn try_key(
+                &self,
+            ) -> ::serde_dynamodb::error::Result<
+                ::std::collections::HashMap<String, ::rusoto_dynamodb::AttributeValue>,
+            > {
+                let mut key = ::std::collections::HashMap::new();
+                #(
+                    key.insert(String::from(#key_names), #key_values);
+                )*
+                Ok(key)
+            }
+
+            fn timestamp(
+                attribute: &str,
+            ) -> ::serde_dynamodb::error::Result<::rusoto_dynamodb::AttributeValue> {
+                match attribute {
+                    #(#timestamps)*
+                    _ => Err(::serde_dynamodb::Error {
+                        message: format!("{} is not a timestamp", attribute),
+                    }),
+                }
+            }
+        }
+    })
+}
+
+/// The function serializing a field, following its `#[serde(with = "...")]` or
+/// `#[serde(serialize_with = "...")]`.
+fn serialize_function(field: &Field) -> TokenStream {
+    match (&field.serialize_with, &field.with) {
+        (Some(function), _) => quote!(#function),
+        (None, Some(module)) => quote!(#module::serialize),
+        (None, None) => quote!(::serde::Serialize::serialize),
+    }
+}
+
+/// The `#[serde(...)]` attributes of a field changing how it is serialized or
+/// deserialized, to copy them to a key struct.
+fn serde_with(field: &Field) -> TokenStream {
+    let path = |path: &syn::Path| quote!(#path).to_string().replace(' ', "");
+    let mut attributes = vec![];
+    if let Some(module) = &field.with {
+        let module = path(module);
+        attributes.push(quote!(#[serde(with = #module)]));
+    }
+    if let Some(function) = &field.serialize_with {
+        let function = path(function);
+        attributes.push(quote!(#[serde(serialize_with = #function)]));
+    }
+    if let Some(function) = &field.deserialize_with {
+        let function = path(function);
+        attributes.push(quote!(#[serde(deserialize_with = #function)]));
+    }
+    quote!(#(#attributes)*)
+}
+
+/// The `CamelCase` form of an index name, to name its key struct.
+fn camel_case(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// A struct named `key_ident` with the fields at positions `fields`, that can be
+/// built from a reference to the item.
+fn key_struct(
+    container: &Container,
+    key_ident: &Ident,
+    doc: &str,
+    fields: &[usize],
+) -> TokenStream {
+    let ident = &container.ident;
+    let vis = &container.vis;
+    let fields: Vec<_> = fields.iter().map(|i| &container.fields[*i]).collect();
+    let declarations = fields.iter().map(|field| {
+        let ident = &field.ident;
+        let ty = &field.ty;
+        let name = &field.name;
+        let doc = format!("The `{}` attribute.", name);
+        let with = serde_with(field);
+        quote! {
+            #[doc = #doc]
+            #[serde(rename = #name)]
+            #with
+            pub #ident: #ty,
+        }
+    });
+    let idents: Vec<_> = fields.iter().map(|field| &field.ident).collect();
+
+    quote! {
+        #[doc = #doc]
+        #[derive(Clone, Debug, PartialEq, ::serde::Serialize, ::serde::Deserialize)]
+        #vis struct #key_ident {
+            #(#declarations)*
+        }
+
+        impl ::std::convert::From<&#ident> for #key_ident {
+            fn from(item: &#ident) -> Self {
+                #key_ident {
+                    #(#idents: ::std::clone::Clone::clone(&item.#idents),)*
+                }
+            }
+        }
+    }
+}
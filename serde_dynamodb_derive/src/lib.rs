@@ -0,0 +1,129 @@
+#![deny(
+    warnings,
+    missing_debug_implementations,
+    missing_copy_implementations,
+    trivial_casts,
+    trivial_numeric_casts,
+    unsafe_code,
+    unstable_features,
+    unused_import_braces,
+    unused_qualifications,
+    missing_docs
+)]
+
+//! Derive macros for [`serde_dynamodb`](https://docs.rs/serde_dynamodb).
+//!
+//! # `ToQueryInput`
+//!
+//! `#[derive(ToQueryInput)]` on a struct `Task` generates a struct `TaskQueryInput`
+//! with the same fields, all optional, that implements `serde_dynamodb::ToQueryInput`.
+//! Fields that are already an `Option` keep their type.
+//! Each field that is set is compared for equality with the attribute of the same
+//! name, taking `#[serde(rename = "...")]` and `#[serde(rename_all = "...")]` into
+//! account.
+//!
+//! Without other annotations, all the fields set are part of the
+//! `KeyConditionExpression`. The keys of the table and of its secondary indexes
+//! can be declared so that only the key attributes are part of it, the others
+//! going to the `FilterExpression`:
+//!
+//! ```rust,ignore
+//! #[derive(Serialize, Deserialize, ToQueryInput)]
+//! #[dynamodb(index = "by_status", partition_key = "status", sort_key = "created")]
+//! struct Task {
+//!     #[dynamodb(partition_key)]
+//!     project: String,
+//!     #[dynamodb(sort_key)]
+//!     id: String,
+//!     status: String,
+//!     created: u64,
+//! }
+//! ```
+//!
+//! The table or the first index whose partition and sort keys are set is queried,
+//! otherwise the table or the first index whose partition key is set.
+//! `try_to_query_input` fails if no partition key is set, or if a value can't be
+//! serialized, and `to_query_input` panics.
+//!
+//! # `DynamoItem`
+//!
+//! `#[derive(DynamoItem)]` implements `serde_dynamodb::DynamoItem` for a struct
+//! with a `#[dynamodb(table = "...")]` attribute and a `#[dynamodb(partition_key)]`
+//! field, and maybe a `#[dynamodb(sort_key)]` field. It also generates a struct
+//! `<Name>Key` with the key fields, that can be built from a `&<Name>`. The key
+//! fields keep their `#[serde(with = "...")]`, `#[serde(serialize_with = "...")]`
+//! and `#[serde(deserialize_with = "...")]`, so the key is serialized like the item.
+//!
+//! ```rust,ignore
+//! #[derive(Serialize, Deserialize, DynamoItem)]
+//! #[dynamodb(table = "tasks")]
+//! struct Task {
+//!     #[dynamodb(partition_key)]
+//!     project: String,
+//!     #[dynamodb(sort_key)]
+//!     id: String,
+//!     title: String,
+//! }
+//!
+//! let key: TaskKey = (&task).into();
+//! ```
+//!
+//! The table can be further described for `serde_dynamodb::create_table_input`:
+//!
+//! * `#[dynamodb(index = "...", partition_key = "...", sort_key = "...")]` declares a
+//!   global secondary index, and `#[dynamodb(local_index = "...", sort_key = "...")]`
+//!   a local secondary index. Both take `projection = "KEYS_ONLY"` or
+//!   `include = "field, field"` if not all attributes are copied to the index.
+//! * `#[dynamodb(read_capacity = 5, write_capacity = 5)]` sets provisioned capacity
+//!   instead of on demand.
+//! * `#[dynamodb(stream = "NEW_AND_OLD_IMAGES")]` enables the stream of the table.
+//! * `#[dynamodb(ttl)]` on a field makes it the time to live attribute. A
+//!   `SystemTime` or a `Duration` from now must be serialized as seconds since the
+//!   epoch, with `#[serde(with = "serde_dynamodb::timestamp::epoch_seconds")]`.
+//!
+//! Some fields are written by a `serde_dynamodb::Table` instead of the caller:
+//!
+//! * `#[dynamodb(version)]` on an integer field makes it the version of the item,
+//!   checked and incremented by each write.
+//! * `#[dynamodb(created_at)]` on an `Option` field is set to the current time when
+//!   the item is created, while it is still `None`.
+//! * `#[dynamodb(updated_at)]` is set to the current time on each write.
+//!
+//! The type of a creation or update time implements
+//! `serde_dynamodb::timestamp::Timestamp`, and it is serialized the same way as
+//! the field, following `#[serde(with = "...")]`.
+//!
+//! Each secondary index also gets a key struct, named after the index: the index
+//! `by_status` of `Task` gets a `TaskByStatusKey` struct with the key fields of the
+//! index and of the table, as found in the `LastEvaluatedKey` of a query on the
+//! index.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+mod attributes;
+mod item;
+mod query_input;
+
+/// Generate a `<Name>QueryInput` struct implementing `serde_dynamodb::ToQueryInput`.
+#[proc_macro_derive(ToQueryInput, attributes(dynamodb))]
+pub fn derive_to_query_input(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    attributes::Container::from_derive_input(&input)
+        .map(|container| query_input::expand(&container))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implement `serde_dynamodb::DynamoItem` and generate the `<Name>Key` struct and
+/// the key structs of the secondary indexes.
+#[proc_macro_derive(DynamoItem, attributes(dynamodb))]
+pub fn derive_dynamo_item(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    attributes::Container::from_derive_input(&input)
+        .and_then(|container| item::expand(&container))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,168 @@
+//! `#[derive(ToQueryInput)]`
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+use crate::attributes::{is_option, Container};
+
+/// The names of the key attributes of the table or of an index.
+fn key_names(container: &Container, partition_key: usize, sort_key: Option<usize>) -> Vec<&str> {
+    std::iter::once(partition_key)
+        .chain(sort_key)
+        .map(|i| container.fields[i].name.as_str())
+        .collect()
+}
+
+/// Choose the table or the index to query, and its key attributes.
+///
+/// Without any key annotation, every field is used in the key condition. Otherwise
+/// the table or the first index whose partition and sort keys are both set is
+/// queried, or else the table or the first index whose partition key is set. Fields
+/// that are not part of the chosen key are used in the filter expression. Without
+/// any partition key set, there is nothing to query and an error is returned.
+fn select_keys(container: &Container) -> TokenStream {
+    let mut candidates = vec![];
+    if let Some(partition_key) = container.partition_key() {
+        candidates.push((None, partition_key, container.sort_key()));
+    }
+    for index in &container.indexes {
+        candidates.push((
+            Some(index.name.as_str()),
+            index.partition_key,
+            index.sort_key,
+        ));
+    }
+
+    if candidates.is_empty() {
+        let names = container
+            .fields
+            .iter()
+            .filter(|field| !field.skipped)
+            .map(|field| &field.name);
+        return quote! {
+            let (index_name, key_attributes): (Option<&str>, &[&str]) = (None, &[#(#names),*]);
+        };
+    }
+
+    let with_sort_key = candidates
+        .iter()
+        .filter(|(_, _, sort_key)| sort_key.is_some())
+        .map(|candidate| (candidate, true));
+    let branches = with_sort_key
+        .chain(candidates.iter().map(|candidate| (candidate, false)))
+        .map(|((index, partition_key, sort_key), with_sort_key)| {
+            let mut set = vec![&container.fields[*partition_key].ident];
+            if with_sort_key {
+                set.extend(sort_key.map(|sort_key| &container.fields[sort_key].ident));
+            }
+            let index = match index {
+                Some(index) => quote!(Some(#index)),
+                None => quote!(None),
+            };
+            let names = key_names(container, *partition_key, *sort_key);
+            quote! {
+                if #(self.#set.is_some())&&* {
+                    (#index, &[#(#names),*])
+                } else
+            }
+        });
+    quote! {
+        let (index_name, key_attributes): (Option<&str>, &[&str]) =
+            #(#branches)* {
+                return Err(::serde_dynamodb::error::Error {
+                    message: format!(
+                        "can't query table {} without the partition key of the table or of an index",
+                        table
+                    ),
+                });
+            };
+    }
+}
+
+pub(crate) fn expand(container: &Container) -> TokenStream {
+    let ident = &container.ident;
+    let vis = &container.vis;
+    let query_ident = format_ident!("{}QueryInput", ident);
+    let fields: Vec<_> = container
+        .fields
+        .iter()
+        .filter(|field| !field.skipped)
+        .collect();
+
+    let struct_doc = format!("Query the items of type [`{}`].", ident);
+    let declarations = fields.iter().map(|field| {
+        let ident = &field.ident;
+        let ty = &field.ty;
+        let doc = format!("Only query items with this `{}`.", field.name);
+        // an optional field isn't nested in another option
+        let ty = if is_option(ty) {
+            quote!(#ty)
+        } else {
+            quote!(Option<#ty>)
+        };
+        quote! {
+            #[doc = #doc]
+            pub #ident: #ty,
+        }
+    });
+
+    let conditions = fields.iter().map(|field| {
+        let ident = &field.ident;
+        let name = &field.name;
+        quote! {
+            if let Some(value) = &self.#ident {
+                let condition = ::serde_dynamodb::expression::Condition::eq(#name, value);
+                if key_attributes.contains(&#name) {
+                    key_conditions.push(condition);
+                } else {
+                    filters.push(condition);
+                }
+            }
+        }
+    });
+
+    let select_keys = select_keys(container);
+
+    quote! {
+        #[doc = #struct_doc]
+        #[derive(Default)]
+        #vis struct #query_ident {
+            #(#declarations)*
+        }
+
+        impl ::serde_dynamodb::ToQueryInput for #query_ident {
+            fn to_query_input(&self, table: String) -> ::rusoto_dynamodb::QueryInput {
+                match self.try_to_query_input(table) {
+                    Ok(input) => input,
+                    Err(error) => panic!("{}", error.message),
+                }
+            }
+
+            fn try_to_query_input(
+                &self,
+                table: String,
+            ) -> ::serde_dynamodb::error::Result<::rusoto_dynamodb::QueryInput> {
+                #select_keys
+                let mut key_conditions = Vec::new();
+                let mut filters = Vec::new();
+                #(#conditions)*
+                let key_condition = match ::serde_dynamodb::expression::Condition::all(key_conditions) {
+                    Some(key_condition) => key_condition,
+                    None => {
+                        return Err(::serde_dynamodb::error::Error {
+                            message: format!("can't query table {} without a key condition", table),
+                        })
+                    }
+                };
+                Ok(::rusoto_dynamodb::QueryInput {
+                    index_name: index_name.map(String::from),
+                    ..::serde_dynamodb::expression::query_input(
+                        table,
+                        key_condition,
+                        ::serde_dynamodb::expression::Condition::all(filters),
+                    )?
+                })
+            }
+        }
+    }
+}
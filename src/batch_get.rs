@@ -0,0 +1,204 @@
+//! Read many items by key with `BatchGetItem`.
+
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use rusoto_core::RusotoError;
+use rusoto_dynamodb::{
+    AttributeValue, BatchGetItemError, BatchGetItemInput, DynamoDb, KeysAndAttributes,
+};
+use serde::de::DeserializeOwned;
+
+use crate::common::same_number;
+use crate::dynamodb::de::from_hashmap;
+use crate::dynamodb::ser::to_hashmap;
+use crate::error::RequestError;
+use crate::retry::RetryPolicy;
+
+/// The maximum number of keys in a `BatchGetItem`.
+const MAX_KEYS: usize = 100;
+
+type Item = HashMap<String, AttributeValue>;
+
+/// The items read by [`batch_get`](fn.batch_get.html).
+#[derive(Clone, Debug, PartialEq)]
+pub struct BatchGetOutput<K: Eq + Hash, T> {
+    /// The keys that were asked for, in order and without duplicates.
+    pub keys: Vec<K>,
+    /// The items that were found, by key.
+    pub items: HashMap<K, T>,
+    /// The keys still unprocessed once the retries were spent, empty if all the
+    /// keys were processed.
+    pub unprocessed_keys: Vec<K>,
+    /// The number of retries that were needed.
+    pub retries: u32,
+}
+
+impl<K: Eq + Hash, T> BatchGetOutput<K, T> {
+    /// The items in the order of their keys, `None` for the keys without an item.
+    pub fn into_ordered(mut self) -> Vec<(K, Option<T>)> {
+        let items = &mut self.items;
+        self.keys
+            .into_iter()
+            .map(|key| {
+                let item = items.remove(&key);
+                (key, item)
+            })
+            .collect()
+    }
+}
+
+/// Whether `item` has the attributes of `key`, comparing numbers by value since
+/// DynamoDB may not return them as they were sent.
+fn has_key(item: &Item, key: &Item) -> bool {
+    key.iter()
+        .all(|(name, value)| match (item.get(name), &value.n) {
+            (Some(AttributeValue { n: Some(a), .. }), Some(b)) => same_number(a, b),
+            (other, _) => other == Some(value),
+        })
+}
+
+/// Read the items of `table_name` with the keys `keys`, retrying unprocessed keys
+/// following the default [`RetryPolicy`](struct.RetryPolicy.html).
+///
+/// Keys are serialized with [`to_hashmap`](fn.to_hashmap.html) and sent in batches
+/// of 100. DynamoDB returns items in any order, so they are matched back to their
+/// key and deserialized with [`from_hashmap`](fn.from_hashmap.html).
+///
+/// ```rust,no_run
+/// use rusoto_core::Region;
+/// use rusoto_dynamodb::DynamoDbClient;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Clone, PartialEq, Eq, Hash, Serialize)]
+/// struct TaskKey {
+///     id: String,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Task {
+///     id: String,
+///     title: String,
+/// }
+///
+/// # async fn run(keys: Vec<TaskKey>) -> Result<(), serde_dynamodb::RequestError<rusoto_dynamodb::BatchGetItemError>> {
+/// let client = DynamoDbClient::new(Region::UsEast1);
+/// let tasks = serde_dynamodb::batch_get::<TaskKey, Task>(&client, String::from("tasks"), keys)
+///     .await?
+///     .into_ordered();
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// This fails if a key couldn't be serialized, if an item couldn't be
+/// deserialized, or if a request failed.
+pub async fn batch_get<K, T>(
+    client: &(impl DynamoDb + Sync + ?Sized),
+    table_name: String,
+    keys: impl IntoIterator<Item = K>,
+) -> Result<BatchGetOutput<K, T>, RequestError<BatchGetItemError>>
+where
+    K: Clone + Eq + Hash + serde::Serialize,
+    T: DeserializeOwned,
+{
+    batch_get_with_retry_policy(client, table_name, keys, RetryPolicy::default()).await
+}
+
+/// Read the items of `table_name` with the keys `keys`, retrying unprocessed keys
+/// following `retry_policy`.
+///
+/// See [`batch_get`](fn.batch_get.html).
+///
+/// # Errors
+///
+/// This fails if a key couldn't be serialized, if an item couldn't be
+/// deserialized, or if a request failed.
+pub async fn batch_get_with_retry_policy<K, T>(
+    client: &(impl DynamoDb + Sync + ?Sized),
+    table_name: String,
+    keys: impl IntoIterator<Item = K>,
+    retry_policy: RetryPolicy,
+) -> Result<BatchGetOutput<K, T>, RequestError<BatchGetItemError>>
+where
+    K: Clone + Eq + Hash + serde::Serialize,
+    T: DeserializeOwned,
+{
+    let mut output = BatchGetOutput {
+        keys: vec![],
+        items: HashMap::new(),
+        unprocessed_keys: vec![],
+        retries: 0,
+    };
+    let mut serialized: Vec<Item> = vec![];
+    let mut seen = HashSet::new();
+    for key in keys {
+        if seen.insert(key.clone()) {
+            serialized.push(to_hashmap(&key)?);
+            output.keys.push(key);
+        }
+    }
+
+    for chunk in output
+        .keys
+        .chunks(MAX_KEYS)
+        .zip(serialized.chunks(MAX_KEYS))
+    {
+        let mut pending: Vec<(&K, &Item)> = chunk.0.iter().zip(chunk.1).collect();
+        loop {
+            let mut request_items = HashMap::new();
+            request_items.insert(
+                table_name.clone(),
+                KeysAndAttributes {
+                    keys: pending.iter().map(|(_, key)| (*key).clone()).collect(),
+                    ..Default::default()
+                },
+            );
+            let result = client
+                .batch_get_item(BatchGetItemInput {
+                    request_items,
+                    ..Default::default()
+                })
+                .await;
+            match result {
+                Ok(result) => {
+                    let items = result
+                        .responses
+                        .and_then(|mut responses| responses.remove(&table_name))
+                        .unwrap_or_default();
+                    for item in items {
+                        if let Some(position) =
+                            pending.iter().position(|(_, key)| has_key(&item, key))
+                        {
+                            let (key, _) = pending.swap_remove(position);
+                            output.items.insert(key.clone(), from_hashmap(item)?);
+                        }
+                    }
+                    let unprocessed = result
+                        .unprocessed_keys
+                        .and_then(|mut unprocessed| unprocessed.remove(&table_name))
+                        .map(|unprocessed| unprocessed.keys)
+                        .unwrap_or_default();
+                    pending.retain(|(_, key)| unprocessed.iter().any(|other| has_key(other, key)));
+                }
+                Err(RusotoError::Service(BatchGetItemError::ProvisionedThroughputExceeded(_)))
+                | Err(RusotoError::Service(BatchGetItemError::RequestLimitExceeded(_)))
+                    if output.retries < retry_policy.max_retries => {}
+                Err(error) => return Err(error.into()),
+            }
+            if pending.is_empty() {
+                break;
+            }
+            if output.retries == retry_policy.max_retries {
+                output
+                    .unprocessed_keys
+                    .extend(pending.into_iter().map(|(key, _)| key.clone()));
+                break;
+            }
+            retry_policy.wait(output.retries).await;
+            output.retries += 1;
+        }
+    }
+    Ok(output)
+}
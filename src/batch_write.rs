@@ -0,0 +1,311 @@
+//! Write many items with `BatchWriteItem`.
+
+use std::collections::HashMap;
+
+use rusoto_core::RusotoError;
+use rusoto_dynamodb::{
+    AttributeValue, BatchWriteItemError, BatchWriteItemInput, DeleteRequest, DynamoDb, PutRequest,
+    WriteRequest,
+};
+
+use crate::common::same_number;
+use crate::dynamodb::ser::to_hashmap;
+use crate::error::{Error, RequestError, Result};
+use crate::item::DynamoItem;
+use crate::retry::RetryPolicy;
+
+/// The maximum number of requests in a `BatchWriteItem`.
+const MAX_REQUESTS: usize = 25;
+/// The maximum size of a `BatchWriteItem`.
+const MAX_SIZE: usize = 16 * 1024 * 1024;
+
+/// The size of an attribute value, as counted by DynamoDB.
+fn value_size(value: &AttributeValue) -> usize {
+    value.s.as_ref().map_or(0, String::len)
+        + value.n.as_ref().map_or(0, |n| n.len() / 2 + 1)
+        + value.b.as_ref().map_or(0, |b| b.len())
+        + value.bool.map_or(0, |_| 1)
+        + value.null.map_or(0, |_| 1)
+        + value.ss.iter().flatten().map(String::len).sum::<usize>()
+        + value
+            .ns
+            .iter()
+            .flatten()
+            .map(|n| n.len() / 2 + 1)
+            .sum::<usize>()
+        + value.bs.iter().flatten().map(|b| b.len()).sum::<usize>()
+        + value.l.as_ref().map_or(0, |l| {
+            3 + l.iter().map(|v| 1 + value_size(v)).sum::<usize>()
+        })
+        + value.m.as_ref().map_or(0, |m| 3 + item_size(m))
+}
+
+/// The size of an item, as counted by DynamoDB for its limits.
+pub(crate) fn item_size(item: &HashMap<String, AttributeValue>) -> usize {
+    item.iter()
+        .map(|(name, value)| name.len() + value_size(value))
+        .sum()
+}
+
+/// Whether two keys are the same, comparing numbers by value since `1` and `1.0`
+/// are the same key for DynamoDB.
+fn same_key(a: &[&AttributeValue], b: &[&AttributeValue]) -> bool {
+    a.iter().zip(b).all(|(a, b)| match (&a.n, &b.n) {
+        (Some(a), Some(b)) => same_number(a, b),
+        _ => a == b,
+    })
+}
+
+/// The size of the item, or of the key, of `request`.
+fn request_size(request: &WriteRequest) -> usize {
+    match (&request.put_request, &request.delete_request) {
+        (Some(put), _) => item_size(&put.item),
+        (None, Some(delete)) => item_size(&delete.key),
+        (None, None) => 0,
+    }
+}
+
+/// What a `BatchWrite` couldn't write.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BatchWriteOutput {
+    /// The requests still unprocessed once the retries were spent, empty if all
+    /// the requests were processed.
+    pub unprocessed_items: Vec<WriteRequest>,
+    /// The number of retries that were needed.
+    pub retries: u32,
+}
+
+/// Puts and deletes on a table, sent with as few `BatchWriteItem` requests as
+/// possible.
+///
+/// Items and keys are serialized with [`to_hashmap`](fn.to_hashmap.html), and split
+/// in batches of at most 25 requests and 16MB. DynamoDB rejects a batch with two
+/// requests on the same item, so only the last request on each key is kept in a
+/// batch.
+///
+/// Unprocessed items, and batches rejected because of throttling, are sent again
+/// following the [`RetryPolicy`](struct.RetryPolicy.html).
+///
+/// ```rust,no_run
+/// use rusoto_core::Region;
+/// use rusoto_dynamodb::DynamoDbClient;
+/// use serde::Serialize;
+/// use serde_dynamodb::BatchWrite;
+///
+/// #[derive(Serialize)]
+/// struct Task {
+///     id: String,
+///     title: String,
+/// }
+///
+/// # async fn run(tasks: Vec<Task>) -> Result<(), serde_dynamodb::RequestError<rusoto_dynamodb::BatchWriteItemError>> {
+/// let client = DynamoDbClient::new(Region::UsEast1);
+/// let output = BatchWrite::new(String::from("tasks"), &["id"])
+///     .put_all(&tasks)
+///     .send(&client)
+///     .await?;
+/// assert!(output.unprocessed_items.is_empty());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct BatchWrite {
+    table_name: String,
+    key_attributes: Vec<String>,
+    requests: Vec<Result<WriteRequest>>,
+    retry_policy: RetryPolicy,
+}
+
+impl BatchWrite {
+    /// Write on `table_name`, whose key is made of `key_attributes`.
+    pub fn new(table_name: String, key_attributes: &[&str]) -> Self {
+        BatchWrite {
+            table_name,
+            key_attributes: key_attributes
+                .iter()
+                .map(|name| (*name).to_owned())
+                .collect(),
+            requests: vec![],
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+
+    /// Write on the table of `T`.
+    pub fn for_table<T: DynamoItem>() -> Self {
+        let key_attributes: Vec<&str> = std::iter::once(T::PARTITION_KEY)
+            .chain(T::SORT_KEY)
+            .collect();
+        BatchWrite::new(T::TABLE_NAME.to_owned(), &key_attributes)
+    }
+
+    /// Put `item`.
+    pub fn put<T>(mut self, item: &T) -> Self
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        self.requests
+            .push(to_hashmap(item).map(|item| WriteRequest {
+                put_request: Some(PutRequest { item }),
+                delete_request: None,
+            }));
+        self
+    }
+
+    /// Put all of `items`.
+    pub fn put_all<I>(self, items: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: serde::Serialize,
+    {
+        items.into_iter().fold(self, |batch, item| batch.put(&item))
+    }
+
+    /// Delete the item with the key `key`.
+    pub fn delete<K>(mut self, key: &K) -> Self
+    where
+        K: ?Sized + serde::Serialize,
+    {
+        self.requests.push(to_hashmap(key).map(|key| WriteRequest {
+            put_request: None,
+            delete_request: Some(DeleteRequest { key }),
+        }));
+        self
+    }
+
+    /// Delete the items with the keys `keys`.
+    pub fn delete_all<I>(self, keys: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: serde::Serialize,
+    {
+        keys.into_iter().fold(self, |batch, key| batch.delete(&key))
+    }
+
+    /// Retry unprocessed items following `retry_policy`.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// The values of the key attributes of the item written by `request`.
+    fn key<'r>(&self, request: &'r WriteRequest) -> Result<Vec<&'r AttributeValue>> {
+        let attributes = match (&request.put_request, &request.delete_request) {
+            (Some(put), _) => &put.item,
+            (None, Some(delete)) => &delete.key,
+            (None, None) => {
+                return Err(Error {
+                    message: String::from("a write request is neither a put nor a delete"),
+                })
+            }
+        };
+        self.key_attributes
+            .iter()
+            .map(|name| {
+                attributes.get(name).ok_or_else(|| Error {
+                    message: format!(
+                        "key attribute {} is missing from a write request on table {}",
+                        name, self.table_name
+                    ),
+                })
+            })
+            .collect()
+    }
+
+    /// The batches of requests to send.
+    ///
+    /// # Errors
+    ///
+    /// This fails if an item or a key couldn't be serialized, or if one of them
+    /// doesn't have all the key attributes.
+    pub fn batches(&self) -> Result<Vec<Vec<WriteRequest>>> {
+        let mut batches = vec![];
+        let mut batch: Vec<WriteRequest> = vec![];
+        let mut keys: Vec<Vec<&AttributeValue>> = vec![];
+        let mut size = 0;
+        for request in &self.requests {
+            let request = request.as_ref().map_err(|error| Error {
+                message: error.message.clone(),
+            })?;
+            let new_size = request_size(request);
+            let key = self.key(request)?;
+            if let Some(previous) = keys.iter().position(|previous| same_key(previous, &key)) {
+                size -= request_size(&batch[previous]);
+                if size + new_size <= MAX_SIZE {
+                    size += new_size;
+                    batch[previous] = request.clone();
+                    continue;
+                }
+                // the last request on a key wins, but it doesn't fit in this batch
+                batch.remove(previous);
+                keys.remove(previous);
+            }
+            if batch.len() == MAX_REQUESTS || size + new_size > MAX_SIZE {
+                batches.push(std::mem::take(&mut batch));
+                keys.clear();
+                size = 0;
+            }
+            size += new_size;
+            batch.push(request.clone());
+            keys.push(key);
+        }
+        if !batch.is_empty() {
+            batches.push(batch);
+        }
+        Ok(batches)
+    }
+
+    /// Send the batches, and retry the unprocessed items.
+    ///
+    /// # Errors
+    ///
+    /// This fails if an item or a key couldn't be serialized or doesn't have all the
+    /// key attributes, in which case nothing is sent, or if a request failed, in
+    /// which case the previous batches were written.
+    pub async fn send<C>(
+        self,
+        client: &C,
+    ) -> std::result::Result<BatchWriteOutput, RequestError<BatchWriteItemError>>
+    where
+        C: DynamoDb + Sync + ?Sized,
+    {
+        let batches = self.batches()?;
+        let mut output = BatchWriteOutput::default();
+        for batch in batches {
+            let mut pending = batch;
+            loop {
+                let mut request_items = HashMap::new();
+                request_items.insert(self.table_name.clone(), pending.clone());
+                let result = client
+                    .batch_write_item(BatchWriteItemInput {
+                        request_items,
+                        ..Default::default()
+                    })
+                    .await;
+                match result {
+                    Ok(result) => {
+                        pending = result
+                            .unprocessed_items
+                            .and_then(|mut unprocessed| unprocessed.remove(&self.table_name))
+                            .unwrap_or_default();
+                    }
+                    Err(RusotoError::Service(
+                        BatchWriteItemError::ProvisionedThroughputExceeded(_),
+                    ))
+                    | Err(RusotoError::Service(BatchWriteItemError::RequestLimitExceeded(_)))
+                        if output.retries < self.retry_policy.max_retries => {}
+                    Err(error) => return Err(error.into()),
+                }
+                if pending.is_empty() {
+                    break;
+                }
+                if output.retries == self.retry_policy.max_retries {
+                    output.unprocessed_items.extend(pending);
+                    break;
+                }
+                self.retry_policy.wait(output.retries).await;
+                output.retries += 1;
+            }
+        }
+        Ok(output)
+    }
+}
@@ -0,0 +1,201 @@
+//! Decode DynamoDB Streams records into typed change events.
+
+use std::collections::HashMap;
+
+use rusoto_dynamodbstreams::{AttributeValue, Record};
+
+use crate::dynamodbstreams::de::from_hashmap;
+use crate::error::{Error, Result};
+
+/// The identity type of the records of items deleted by Time to Live.
+const TTL_IDENTITY_TYPE: &str = "Service";
+/// The principal of the records of items deleted by Time to Live.
+const TTL_PRINCIPAL_ID: &str = "dynamodb.amazonaws.com";
+
+/// The type of data modification that was performed on the DynamoDB table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EventName {
+    /// A new item was added to the table.
+    Insert,
+    /// One or more of an existing item's attributes were modified.
+    Modify,
+    /// The item was deleted from the table.
+    Remove,
+}
+
+impl std::str::FromStr for EventName {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "INSERT" => Ok(EventName::Insert),
+            "MODIFY" => Ok(EventName::Modify),
+            "REMOVE" => Ok(EventName::Remove),
+            _ => Err(Error {
+                message: format!("unknown event name {:?}", s),
+            }),
+        }
+    }
+}
+
+/// What information is written to the stream when an item is modified.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StreamViewType {
+    /// Only the key attributes of the modified item.
+    KeysOnly,
+    /// The entire item, as it appears after it was modified.
+    NewImage,
+    /// The entire item, as it appeared before it was modified.
+    OldImage,
+    /// Both the new and the old images of the item.
+    NewAndOldImages,
+}
+
+impl StreamViewType {
+    /// Whether a record of this view type carries the new image of an `event_name` event.
+    pub fn has_new_image(self, event_name: EventName) -> bool {
+        match self {
+            StreamViewType::NewImage | StreamViewType::NewAndOldImages => {
+                event_name != EventName::Remove
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether a record of this view type carries the old image of an `event_name` event.
+    pub fn has_old_image(self, event_name: EventName) -> bool {
+        match self {
+            StreamViewType::OldImage | StreamViewType::NewAndOldImages => {
+                event_name != EventName::Insert
+            }
+            _ => false,
+        }
+    }
+}
+
+impl std::str::FromStr for StreamViewType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "KEYS_ONLY" => Ok(StreamViewType::KeysOnly),
+            "NEW_IMAGE" => Ok(StreamViewType::NewImage),
+            "OLD_IMAGE" => Ok(StreamViewType::OldImage),
+            "NEW_AND_OLD_IMAGES" => Ok(StreamViewType::NewAndOldImages),
+            _ => Err(Error {
+                message: format!("unknown stream view type {:?}", s),
+            }),
+        }
+    }
+}
+
+/// A typed change event, decoded from a DynamoDB Streams [`Record`][record].
+///
+/// `K` is the type of the item's key attributes and `T` the type of the item.
+///
+/// [record]: https://rusoto.github.io/rusoto/rusoto_dynamodbstreams/struct.Record.html
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChangeEvent<K, T> {
+    /// A globally unique identifier for the event.
+    pub event_id: Option<String>,
+    /// The type of data modification that was performed.
+    pub event_name: EventName,
+    /// The key attributes of the modified item.
+    pub keys: K,
+    /// The item as it appeared before it was modified, if the stream carries it.
+    pub old_image: Option<T>,
+    /// The item as it appears after it was modified, if the stream carries it.
+    pub new_image: Option<T>,
+    /// The sequence number of the stream record.
+    pub sequence_number: Option<String>,
+    /// The approximate date and time when the stream record was created, in seconds since epoch.
+    pub approximate_creation_date_time: Option<f64>,
+    /// The type of data written to the stream.
+    pub stream_view_type: Option<StreamViewType>,
+    /// Whether the item was removed by DynamoDB after its Time to Live expired,
+    /// rather than by a user.
+    pub removed_by_ttl: bool,
+}
+
+impl<K, T> ChangeEvent<K, T>
+where
+    K: serde::de::DeserializeOwned,
+    T: serde::de::DeserializeOwned,
+{
+    /// Decode a change event from a DynamoDB Streams `Record`.
+    ///
+    /// # Errors
+    ///
+    /// This conversion fails if the record is missing its event name or keys, if
+    /// its `StreamViewType` says an image should be present for this event but it
+    /// is missing, or if the keys or images can't be deserialized.
+    pub fn from_record(record: Record) -> Result<Self> {
+        let event_name = record
+            .event_name
+            .as_deref()
+            .ok_or_else(|| Error {
+                message: "missing event name".to_owned(),
+            })?
+            .parse::<EventName>()?;
+        let removed_by_ttl = event_name == EventName::Remove
+            && matches!(
+                record.user_identity,
+                Some(ref identity) if identity.type_.as_deref() == Some(TTL_IDENTITY_TYPE)
+                    && identity.principal_id.as_deref() == Some(TTL_PRINCIPAL_ID)
+            );
+        let stream_record = record.dynamodb.ok_or_else(|| Error {
+            message: "missing stream record".to_owned(),
+        })?;
+        let stream_view_type = stream_record
+            .stream_view_type
+            .as_deref()
+            .map(str::parse::<StreamViewType>)
+            .transpose()?;
+
+        let keys = from_hashmap(stream_record.keys.ok_or_else(|| Error {
+            message: "missing keys".to_owned(),
+        })?)?;
+        let new_image = image(
+            stream_record.new_image,
+            stream_view_type.map(|view_type| view_type.has_new_image(event_name)),
+            "NewImage",
+            event_name,
+        )?;
+        let old_image = image(
+            stream_record.old_image,
+            stream_view_type.map(|view_type| view_type.has_old_image(event_name)),
+            "OldImage",
+            event_name,
+        )?;
+
+        Ok(ChangeEvent {
+            event_id: record.event_id,
+            event_name,
+            keys,
+            old_image,
+            new_image,
+            sequence_number: stream_record.sequence_number,
+            approximate_creation_date_time: stream_record.approximate_creation_date_time,
+            stream_view_type,
+            removed_by_ttl,
+        })
+    }
+}
+
+fn image<T>(
+    image: Option<HashMap<String, AttributeValue>>,
+    expected: Option<bool>,
+    name: &str,
+    event_name: EventName,
+) -> Result<Option<T>>
+where
+    T: serde::de::DeserializeOwned,
+{
+    match (expected, image) {
+        (Some(false), _) => Ok(None),
+        (Some(true), None) => Err(Error {
+            message: format!("missing {} for {:?} event", name, event_name),
+        }),
+        (_, image) => image.map(from_hashmap).transpose(),
+    }
+}
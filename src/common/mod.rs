@@ -1,5 +1,7 @@
+#[cfg(any(feature = "rusoto_dynamodb", feature = "rusoto_dynamodbstreams"))]
 use crate::error::{Error, Result};
 
+#[cfg(any(feature = "rusoto_dynamodb", feature = "rusoto_dynamodbstreams"))]
 macro_rules! impl_serialize_to_string {
     ($type:ty, $method:ident) => {
         fn $method(self, value: $type) -> Result<()> {
@@ -9,22 +11,27 @@ macro_rules! impl_serialize_to_string {
     };
 }
 
+#[cfg(any(feature = "rusoto_dynamodb", feature = "rusoto_dynamodbstreams"))]
 #[derive(Debug)]
 pub(crate) struct SimpleKeySerializer {
     result: Option<String>,
 }
 
+#[cfg(any(feature = "rusoto_dynamodb", feature = "rusoto_dynamodbstreams"))]
 impl SimpleKeySerializer {
     #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         SimpleKeySerializer { result: None }
     }
 
-    pub fn get_result(self) -> String {
-        self.result.expect("error serializing key")
+    pub fn get_result(self) -> Result<String> {
+        self.result.ok_or_else(|| Error {
+            message: String::from("can't serialize as a key"),
+        })
     }
 }
-impl<'a> serde::Serializer for &'a mut SimpleKeySerializer {
+#[cfg(any(feature = "rusoto_dynamodb", feature = "rusoto_dynamodbstreams"))]
+impl serde::Serializer for &mut SimpleKeySerializer {
     type Ok = ();
     type Error = Error;
 
@@ -79,9 +86,9 @@ impl<'a> serde::Serializer for &'a mut SimpleKeySerializer {
         })
     }
 
-    fn serialize_newtype_struct<T: ?Sized>(self, _name: &'static str, _value: &T) -> Result<()>
+    fn serialize_newtype_struct<T>(self, _name: &'static str, _value: &T) -> Result<()>
     where
-        T: serde::ser::Serialize,
+        T: ?Sized + serde::ser::Serialize,
     {
         Err(Error {
             message: String::from("can't serialize as a key as it's of type newtype struct"),
@@ -89,7 +96,7 @@ impl<'a> serde::Serializer for &'a mut SimpleKeySerializer {
     }
 
     #[inline]
-    fn serialize_newtype_variant<T: ?Sized>(
+    fn serialize_newtype_variant<T>(
         self,
         _name: &'static str,
         _variant_index: u32,
@@ -97,7 +104,7 @@ impl<'a> serde::Serializer for &'a mut SimpleKeySerializer {
         _value: &T,
     ) -> Result<()>
     where
-        T: serde::ser::Serialize,
+        T: ?Sized + serde::ser::Serialize,
     {
         Err(Error {
             message: String::from("can't serialize as a key as it's of type newtype variant"),
@@ -110,9 +117,9 @@ impl<'a> serde::Serializer for &'a mut SimpleKeySerializer {
         })
     }
 
-    fn serialize_some<T: ?Sized>(self, _value: &T) -> Result<()>
+    fn serialize_some<T>(self, _value: &T) -> Result<()>
     where
-        T: serde::ser::Serialize,
+        T: ?Sized + serde::ser::Serialize,
     {
         Err(Error {
             message: String::from("can't serialize as a key as it's of type unit option"),
@@ -178,13 +185,14 @@ impl<'a> serde::Serializer for &'a mut SimpleKeySerializer {
     }
 }
 
+#[cfg(any(feature = "rusoto_dynamodb", feature = "rusoto_dynamodbstreams"))]
 impl serde::ser::SerializeSeq for Compound {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_element<T: ?Sized>(&mut self, _value: &T) -> Result<()>
+    fn serialize_element<T>(&mut self, _value: &T) -> Result<()>
     where
-        T: serde::ser::Serialize,
+        T: ?Sized + serde::ser::Serialize,
     {
         unreachable!()
     }
@@ -194,15 +202,17 @@ impl serde::ser::SerializeSeq for Compound {
     }
 }
 
+#[cfg(any(feature = "rusoto_dynamodb", feature = "rusoto_dynamodbstreams"))]
 #[derive(Clone, Copy, Debug)]
 pub(crate) struct Compound;
+#[cfg(any(feature = "rusoto_dynamodb", feature = "rusoto_dynamodbstreams"))]
 impl serde::ser::SerializeTuple for Compound {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_element<T: ?Sized>(&mut self, _value: &T) -> Result<()>
+    fn serialize_element<T>(&mut self, _value: &T) -> Result<()>
     where
-        T: serde::ser::Serialize,
+        T: ?Sized + serde::ser::Serialize,
     {
         unreachable!()
     }
@@ -213,13 +223,14 @@ impl serde::ser::SerializeTuple for Compound {
     }
 }
 
+#[cfg(any(feature = "rusoto_dynamodb", feature = "rusoto_dynamodbstreams"))]
 impl serde::ser::SerializeTupleStruct for Compound {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_field<T: ?Sized>(&mut self, _value: &T) -> Result<()>
+    fn serialize_field<T>(&mut self, _value: &T) -> Result<()>
     where
-        T: serde::ser::Serialize,
+        T: ?Sized + serde::ser::Serialize,
     {
         unreachable!()
     }
@@ -229,13 +240,14 @@ impl serde::ser::SerializeTupleStruct for Compound {
     }
 }
 
+#[cfg(any(feature = "rusoto_dynamodb", feature = "rusoto_dynamodbstreams"))]
 impl serde::ser::SerializeTupleVariant for Compound {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_field<T: ?Sized>(&mut self, _value: &T) -> Result<()>
+    fn serialize_field<T>(&mut self, _value: &T) -> Result<()>
     where
-        T: serde::ser::Serialize,
+        T: ?Sized + serde::ser::Serialize,
     {
         unreachable!()
     }
@@ -245,20 +257,21 @@ impl serde::ser::SerializeTupleVariant for Compound {
     }
 }
 
+#[cfg(any(feature = "rusoto_dynamodb", feature = "rusoto_dynamodbstreams"))]
 impl serde::ser::SerializeMap for Compound {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_key<T: ?Sized>(&mut self, _key: &T) -> Result<()>
+    fn serialize_key<T>(&mut self, _key: &T) -> Result<()>
     where
-        T: serde::ser::Serialize,
+        T: ?Sized + serde::ser::Serialize,
     {
         unreachable!()
     }
 
-    fn serialize_value<T: ?Sized>(&mut self, _value: &T) -> Result<()>
+    fn serialize_value<T>(&mut self, _value: &T) -> Result<()>
     where
-        T: serde::ser::Serialize,
+        T: ?Sized + serde::ser::Serialize,
     {
         unreachable!()
     }
@@ -268,13 +281,14 @@ impl serde::ser::SerializeMap for Compound {
     }
 }
 
+#[cfg(any(feature = "rusoto_dynamodb", feature = "rusoto_dynamodbstreams"))]
 impl serde::ser::SerializeStruct for Compound {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_field<T: ?Sized>(&mut self, _key: &'static str, _value: &T) -> Result<()>
+    fn serialize_field<T>(&mut self, _key: &'static str, _value: &T) -> Result<()>
     where
-        T: serde::ser::Serialize,
+        T: ?Sized + serde::ser::Serialize,
     {
         unreachable!()
     }
@@ -284,13 +298,14 @@ impl serde::ser::SerializeStruct for Compound {
     }
 }
 
+#[cfg(any(feature = "rusoto_dynamodb", feature = "rusoto_dynamodbstreams"))]
 impl serde::ser::SerializeStructVariant for Compound {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_field<T: ?Sized>(&mut self, _key: &'static str, _value: &T) -> Result<()>
+    fn serialize_field<T>(&mut self, _key: &'static str, _value: &T) -> Result<()>
     where
-        T: serde::ser::Serialize,
+        T: ?Sized + serde::ser::Serialize,
     {
         unreachable!()
     }
@@ -299,3 +314,63 @@ impl serde::ser::SerializeStructVariant for Compound {
         unreachable!()
     }
 }
+
+/// A number in a canonical form: `1.50`, `1.5` and `15E-1` are the same number.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct CanonicalNumber {
+    negative: bool,
+    /// The significant digits, without leading or trailing zeros.
+    pub digits: String,
+    /// The exponent of the first significant digit.
+    pub exponent: i64,
+}
+
+impl CanonicalNumber {
+    /// Parse a number as written in an `N`, or `None` if it isn't a number.
+    pub fn parse(number: &str) -> Option<Self> {
+        let (negative, unsigned) = match number.strip_prefix('-') {
+            Some(unsigned) => (true, unsigned),
+            None => (false, number.strip_prefix('+').unwrap_or(number)),
+        };
+        let (mantissa, exponent): (&str, i64) = match unsigned.find(['e', 'E']) {
+            Some(index) => (&unsigned[..index], unsigned[index + 1..].parse().ok()?),
+            None => (unsigned, 0),
+        };
+        let (integer, fraction) = match mantissa.find('.') {
+            Some(index) => (&mantissa[..index], &mantissa[index + 1..]),
+            None => (mantissa, ""),
+        };
+        let digits: String = integer.chars().chain(fraction.chars()).collect();
+        if digits.is_empty() || !digits.bytes().all(|digit| digit.is_ascii_digit()) {
+            return None;
+        }
+        let first = match digits.find(|digit| digit != '0') {
+            Some(first) => first,
+            None => {
+                return Some(CanonicalNumber {
+                    negative: false,
+                    digits: String::new(),
+                    exponent: 0,
+                })
+            }
+        };
+        let significant = digits[first..].trim_end_matches('0');
+        let exponent = exponent
+            .checked_add(integer.len() as i64)?
+            .checked_sub(1 + first as i64)?;
+        Some(CanonicalNumber {
+            negative,
+            digits: String::from(significant),
+            exponent,
+        })
+    }
+}
+
+/// Check if two `N` are the same number, however they are written.
+#[cfg(any(feature = "rusoto_dynamodb", feature = "rusoto_dynamodbstreams"))]
+pub(crate) fn same_number(a: &str, b: &str) -> bool {
+    match (CanonicalNumber::parse(a), CanonicalNumber::parse(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => a == b,
+    }
+}
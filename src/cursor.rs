@@ -0,0 +1,174 @@
+//! Opaque pagination cursors built from a `LastEvaluatedKey`.
+
+use std::collections::HashMap;
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use rusoto_dynamodb::AttributeValue;
+use sha2::Sha256;
+
+use crate::error::{Error, Result};
+
+const SIGNATURE_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+const PLAIN: u8 = 0;
+const ENCRYPTED: u8 = 1;
+
+/// The secrets used to sign, and maybe encrypt, cursors.
+///
+/// A signed cursor can't be modified by clients, but they can still decode the
+/// key it holds. An encrypted cursor also keeps the key secret.
+#[derive(Clone)]
+pub struct CursorKey {
+    signing_key: Vec<u8>,
+    encryption_key: Option<[u8; 32]>,
+}
+
+impl std::fmt::Debug for CursorKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("CursorKey")
+            .field("encrypted", &self.encryption_key.is_some())
+            .finish()
+    }
+}
+
+impl CursorKey {
+    /// Sign cursors with HMAC-SHA256 and `signing_key`.
+    pub fn new(signing_key: &[u8]) -> Self {
+        CursorKey {
+            signing_key: signing_key.to_vec(),
+            encryption_key: None,
+        }
+    }
+
+    /// Also encrypt cursors with AES-256-GCM and `encryption_key`, which should be
+    /// different from the signing key.
+    pub fn with_encryption(mut self, encryption_key: [u8; 32]) -> Self {
+        self.encryption_key = Some(encryption_key);
+        self
+    }
+
+    /// The HMAC of a cursor read in `context`, fed with the context first.
+    fn mac(&self, context: &str) -> Hmac<Sha256> {
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&self.signing_key)
+            .expect("HMAC accepts keys of any size");
+        mac.update(&(context.len() as u64).to_be_bytes());
+        mac.update(context.as_bytes());
+        mac
+    }
+}
+
+fn invalid() -> Error {
+    Error {
+        message: String::from("invalid cursor"),
+    }
+}
+
+/// Encode a `LastEvaluatedKey` as an opaque cursor, safe to use in URLs.
+///
+/// The key is serialized as JSON, encrypted if `key` has an encryption key,
+/// signed, and encoded in base64url.
+///
+/// The signature also covers `context`, which tells what is being read, such as
+/// the table, the index and the key condition of a query. The cursor can only be
+/// decoded with the same context, so that it can't be used to resume another
+/// query.
+///
+/// # Errors
+///
+/// This fails if the key couldn't be serialized or encrypted.
+pub fn encode_cursor<S: std::hash::BuildHasher>(
+    last_evaluated_key: &HashMap<String, AttributeValue, S>,
+    context: &str,
+    key: &CursorKey,
+) -> Result<String> {
+    let json = serde_json::to_vec(last_evaluated_key).map_err(|error| Error {
+        message: error.to_string(),
+    })?;
+    let mut data = match key.encryption_key {
+        None => {
+            let mut data = vec![PLAIN];
+            data.extend(json);
+            data
+        }
+        Some(encryption_key) => {
+            let cipher = Aes256Gcm::new(&encryption_key.into());
+            let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+            let encrypted = cipher.encrypt(&nonce, json.as_slice()).map_err(|_| Error {
+                message: String::from("the cursor couldn't be encrypted"),
+            })?;
+            let mut data = vec![ENCRYPTED];
+            data.extend(nonce);
+            data.extend(encrypted);
+            data
+        }
+    };
+    let mut mac = key.mac(context);
+    mac.update(&data);
+    data.extend(mac.finalize().into_bytes());
+    Ok(URL_SAFE_NO_PAD.encode(data))
+}
+
+/// Decode a cursor made by [`encode_cursor`](fn.encode_cursor.html) into the
+/// `ExclusiveStartKey` to resume from.
+///
+/// The key must have exactly the attributes of `key_schema`, the key attributes of
+/// the table or of the index being read, each being a string, a number or bytes.
+///
+/// # Errors
+///
+/// This fails with an `invalid cursor` error if the cursor wasn't made with `key`
+/// and `context`, was modified, or doesn't hold a key with the attributes of
+/// `key_schema`.
+pub fn decode_cursor(
+    cursor: &str,
+    context: &str,
+    key: &CursorKey,
+    key_schema: &[&str],
+) -> Result<HashMap<String, AttributeValue>> {
+    let data = URL_SAFE_NO_PAD.decode(cursor).map_err(|_| invalid())?;
+    if data.len() < 1 + SIGNATURE_LEN {
+        return Err(invalid());
+    }
+    let (data, signature) = data.split_at(data.len() - SIGNATURE_LEN);
+    let mut mac = key.mac(context);
+    mac.update(data);
+    mac.verify_slice(signature).map_err(|_| invalid())?;
+
+    let json = match (data[0], key.encryption_key) {
+        (PLAIN, None) => data[1..].to_vec(),
+        (ENCRYPTED, Some(encryption_key)) if data.len() > 1 + NONCE_LEN => {
+            let cipher = Aes256Gcm::new(&encryption_key.into());
+            let (nonce, encrypted) = data[1..].split_at(NONCE_LEN);
+            cipher
+                .decrypt(Nonce::from_slice(nonce), encrypted)
+                .map_err(|_| invalid())?
+        }
+        _ => return Err(invalid()),
+    };
+    let last_evaluated_key: HashMap<String, AttributeValue> =
+        serde_json::from_slice(&json).map_err(|_| invalid())?;
+
+    let is_key = |(name, value): (&String, &AttributeValue)| {
+        key_schema.contains(&name.as_str())
+            && [value.s.is_some(), value.n.is_some(), value.b.is_some()]
+                .iter()
+                .filter(|set| **set)
+                .count()
+                == 1
+            && value
+                == &AttributeValue {
+                    s: value.s.clone(),
+                    n: value.n.clone(),
+                    b: value.b.clone(),
+                    ..Default::default()
+                }
+    };
+    if last_evaluated_key.len() != key_schema.len() || !last_evaluated_key.iter().all(is_key) {
+        return Err(invalid());
+    }
+    Ok(last_evaluated_key)
+}
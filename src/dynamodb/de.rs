@@ -80,7 +80,7 @@ impl Read for VecRead {
         }
     }
     fn get_keys(&self) -> Vec<String> {
-        return vec![];
+        vec![]
     }
 }
 
@@ -90,7 +90,7 @@ struct Deserializer<R> {
     current_field: Index,
     as_key: bool,
 }
-impl<'de, R> Deserializer<R>
+impl<R> Deserializer<R>
 where
     R: Read,
 {
@@ -103,7 +103,7 @@ where
     }
 }
 
-impl<'de, 'a, R: Read> serde::de::Deserializer<'de> for &'a mut Deserializer<R> {
+impl<'de, R: Read> serde::de::Deserializer<'de> for &mut Deserializer<R> {
     type Error = Error;
 
     fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
@@ -287,10 +287,20 @@ impl<'de, 'a, R: Read> serde::de::Deserializer<'de> for &'a mut Deserializer<R>
         self.deserialize_unit(visitor)
     }
 
-    fn deserialize_newtype_struct<V>(self, _name: &str, visitor: V) -> Result<V::Value>
+    fn deserialize_newtype_struct<V>(self, name: &str, visitor: V) -> Result<V::Value>
     where
         V: serde::de::Visitor<'de>,
     {
+        if name == crate::number::TOKEN {
+            let number = self
+                .read
+                .get_attribute_value(&self.current_field)
+                .and_then(|field| field.n.clone())
+                .ok_or_else(|| Error {
+                    message: format!("missing number for field {:?}", &self.current_field),
+                })?;
+            return visitor.visit_newtype_struct(number.into_deserializer());
+        }
         visitor.visit_newtype_struct(self)
     }
 
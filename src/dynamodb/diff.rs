@@ -0,0 +1,265 @@
+//! Compute the changes between two versions of an item.
+
+use std::collections::{BTreeSet, HashMap};
+
+use rusoto_dynamodb::AttributeValue;
+
+use crate::common::same_number;
+use crate::error::Result;
+use crate::path::{AttributePath, PathElement};
+
+/// A change between two versions of an item.
+///
+/// Maps and lists are compared attribute by attribute and element by element,
+/// so changes can be nested deep into an item. Sets are compared member by
+/// member: the members added or removed are given as a set of the same type.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum Change {
+    /// An attribute, or a list element, was added.
+    Added {
+        /// The path to the new attribute.
+        path: AttributePath,
+        /// Its value.
+        value: AttributeValue,
+    },
+    /// An attribute, or a list element, was removed.
+    Removed {
+        /// The path to the removed attribute.
+        path: AttributePath,
+        /// Its previous value.
+        value: AttributeValue,
+    },
+    /// The value of an attribute changed.
+    Changed {
+        /// The path to the attribute.
+        path: AttributePath,
+        /// Its previous value.
+        old: AttributeValue,
+        /// Its new value.
+        new: AttributeValue,
+    },
+    /// Members were added to a set.
+    MembersAdded {
+        /// The path to the set.
+        path: AttributePath,
+        /// The members that were added, as a set.
+        members: AttributeValue,
+    },
+    /// Members were removed from a set.
+    MembersRemoved {
+        /// The path to the set.
+        path: AttributePath,
+        /// The members that were removed, as a set.
+        members: AttributeValue,
+    },
+}
+
+impl Change {
+    /// The path to the attribute this change is about.
+    pub fn path(&self) -> &AttributePath {
+        match self {
+            Change::Added { path, .. }
+            | Change::Removed { path, .. }
+            | Change::Changed { path, .. }
+            | Change::MembersAdded { path, .. }
+            | Change::MembersRemoved { path, .. } => path,
+        }
+    }
+}
+
+/// Get the value at `path` in `item`, if there is one.
+pub fn get_path<'a, S: ::std::hash::BuildHasher>(
+    item: &'a HashMap<String, AttributeValue, S>,
+    path: &AttributePath,
+) -> Option<&'a AttributeValue> {
+    let mut elements = path.elements.iter();
+    let mut value = match elements.next() {
+        Some(PathElement::Attribute(name)) => item.get(name)?,
+        _ => return None,
+    };
+    for element in elements {
+        value = match element {
+            PathElement::Attribute(name) => value.m.as_ref()?.get(name)?,
+            PathElement::Index(index) => value.l.as_ref()?.get(*index)?,
+        };
+    }
+    Some(value)
+}
+
+fn diff_maps<S: ::std::hash::BuildHasher>(
+    path: &AttributePath,
+    old: &HashMap<String, AttributeValue, S>,
+    new: &HashMap<String, AttributeValue, S>,
+    changes: &mut Vec<Change>,
+) {
+    let names: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    for name in names {
+        let path = path.with_attribute(name);
+        match (old.get(name), new.get(name)) {
+            (Some(old), Some(new)) => diff_values(path, old, new, changes),
+            (Some(old), None) => changes.push(Change::Removed {
+                path,
+                value: old.clone(),
+            }),
+            (None, Some(new)) => changes.push(Change::Added {
+                path,
+                value: new.clone(),
+            }),
+            (None, None) => unreachable!(),
+        }
+    }
+}
+
+fn diff_lists(
+    path: &AttributePath,
+    old: &[AttributeValue],
+    new: &[AttributeValue],
+    changes: &mut Vec<Change>,
+) {
+    for (index, (old, new)) in old.iter().zip(new.iter()).enumerate() {
+        diff_values(path.with_index(index), old, new, changes);
+    }
+    for (index, old) in old.iter().enumerate().skip(new.len()) {
+        changes.push(Change::Removed {
+            path: path.with_index(index),
+            value: old.clone(),
+        });
+    }
+    for (index, new) in new.iter().enumerate().skip(old.len()) {
+        changes.push(Change::Added {
+            path: path.with_index(index),
+            value: new.clone(),
+        });
+    }
+}
+
+fn diff_sets<T, F, E>(
+    path: AttributePath,
+    old: &[T],
+    new: &[T],
+    to_set: F,
+    same: E,
+    changes: &mut Vec<Change>,
+) where
+    T: Clone,
+    F: Fn(Vec<T>) -> AttributeValue,
+    E: Fn(&T, &T) -> bool,
+{
+    let missing_from = |members: &[T], member: &T| !members.iter().any(|other| same(other, member));
+    let added: Vec<T> = new
+        .iter()
+        .filter(|member| missing_from(old, member))
+        .cloned()
+        .collect();
+    let removed: Vec<T> = old
+        .iter()
+        .filter(|member| missing_from(new, member))
+        .cloned()
+        .collect();
+    if !added.is_empty() {
+        changes.push(Change::MembersAdded {
+            path: path.clone(),
+            members: to_set(added),
+        });
+    }
+    if !removed.is_empty() {
+        changes.push(Change::MembersRemoved {
+            path,
+            members: to_set(removed),
+        });
+    }
+}
+
+fn diff_values(
+    path: AttributePath,
+    old: &AttributeValue,
+    new: &AttributeValue,
+    changes: &mut Vec<Change>,
+) {
+    if old == new {
+        return;
+    }
+    match (old, new) {
+        (AttributeValue { n: Some(old), .. }, AttributeValue { n: Some(new), .. })
+            if same_number(old, new) => {}
+        (AttributeValue { m: Some(old), .. }, AttributeValue { m: Some(new), .. }) => {
+            diff_maps(&path, old, new, changes)
+        }
+        (AttributeValue { l: Some(old), .. }, AttributeValue { l: Some(new), .. }) => {
+            diff_lists(&path, old, new, changes)
+        }
+        (AttributeValue { ss: Some(old), .. }, AttributeValue { ss: Some(new), .. }) => diff_sets(
+            path,
+            old,
+            new,
+            |ss| AttributeValue {
+                ss: Some(ss),
+                ..Default::default()
+            },
+            |a, b| a == b,
+            changes,
+        ),
+        (AttributeValue { ns: Some(old), .. }, AttributeValue { ns: Some(new), .. }) => diff_sets(
+            path,
+            old,
+            new,
+            |ns| AttributeValue {
+                ns: Some(ns),
+                ..Default::default()
+            },
+            |a, b| same_number(a, b),
+            changes,
+        ),
+        (AttributeValue { bs: Some(old), .. }, AttributeValue { bs: Some(new), .. }) => diff_sets(
+            path,
+            old,
+            new,
+            |bs| AttributeValue {
+                bs: Some(bs),
+                ..Default::default()
+            },
+            |a, b| a == b,
+            changes,
+        ),
+        _ => changes.push(Change::Changed {
+            path,
+            old: old.clone(),
+            new: new.clone(),
+        }),
+    }
+}
+
+/// Compute the changes between two versions of an item.
+///
+/// Changes are ordered by attribute name, then by list index.
+pub fn diff_items<S: ::std::hash::BuildHasher>(
+    old: &HashMap<String, AttributeValue, S>,
+    new: &HashMap<String, AttributeValue, S>,
+) -> Vec<Change> {
+    let mut changes = vec![];
+    diff_maps(&AttributePath::default(), old, new, &mut changes);
+    changes
+}
+
+/// Compute the changes between two versions of an item, after deserializing
+/// both of them as a `T`.
+///
+/// Only the attributes known to `T` are compared, and both sides are compared
+/// in the form `T` would be serialized to.
+///
+/// # Errors
+///
+/// This can fail if one of the versions can't be deserialized as a `T`, or if
+/// `T` can't be serialized back.
+pub fn diff<T, S: ::std::hash::BuildHasher + Clone>(
+    old: HashMap<String, AttributeValue, S>,
+    new: HashMap<String, AttributeValue, S>,
+) -> Result<Vec<Change>>
+where
+    T: serde::Serialize + serde::de::DeserializeOwned,
+{
+    let old = super::ser::to_hashmap(&super::de::from_hashmap::<T, S>(old)?)?;
+    let new = super::ser::to_hashmap(&super::de::from_hashmap::<T, S>(new)?)?;
+    Ok(diff_items(&old, &new))
+}
@@ -1,2 +1,5 @@
 pub mod de;
+pub mod diff;
+#[cfg(feature = "json-patch")]
+pub mod json_patch;
 pub mod ser;
@@ -0,0 +1,228 @@
+// generated file, see update_streams.sh
+
+//! Convert the changes between two versions of an item into a JSON Patch.
+
+use std::collections::HashMap;
+
+use rusoto_dynamodbstreams::AttributeValue;
+
+use super::diff::{diff_items, get_path, Change};
+use crate::common::same_number;
+use crate::error::{Error, Result};
+use crate::path::{AttributePath, PathElement};
+
+/// An operation of a [JSON Patch](https://tools.ietf.org/html/rfc6902).
+#[derive(Clone, Debug, PartialEq)]
+pub enum PatchOperation {
+    /// Add a value, or insert it in an array.
+    Add {
+        /// The JSON Pointer to the value.
+        path: String,
+        /// The value to add.
+        value: serde_json::Value,
+    },
+    /// Remove a value.
+    Remove {
+        /// The JSON Pointer to the value.
+        path: String,
+    },
+    /// Replace a value.
+    Replace {
+        /// The JSON Pointer to the value.
+        path: String,
+        /// The new value.
+        value: serde_json::Value,
+    },
+}
+
+impl PatchOperation {
+    /// The JSON Pointer this operation applies to.
+    pub fn path(&self) -> &str {
+        match self {
+            PatchOperation::Add { path, .. }
+            | PatchOperation::Remove { path }
+            | PatchOperation::Replace { path, .. } => path,
+        }
+    }
+}
+
+impl serde::Serialize for PatchOperation {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        match self {
+            PatchOperation::Add { path, value } => {
+                let mut state = serializer.serialize_struct("PatchOperation", 3)?;
+                state.serialize_field("op", "add")?;
+                state.serialize_field("path", path)?;
+                state.serialize_field("value", value)?;
+                state.end()
+            }
+            PatchOperation::Remove { path } => {
+                let mut state = serializer.serialize_struct("PatchOperation", 2)?;
+                state.serialize_field("op", "remove")?;
+                state.serialize_field("path", path)?;
+                state.end()
+            }
+            PatchOperation::Replace { path, value } => {
+                let mut state = serializer.serialize_struct("PatchOperation", 3)?;
+                state.serialize_field("op", "replace")?;
+                state.serialize_field("path", path)?;
+                state.serialize_field("value", value)?;
+                state.end()
+            }
+        }
+    }
+}
+
+fn to_json_number(n: &str) -> Result<serde_json::Value> {
+    if let Ok(value) = n.parse::<i64>() {
+        return Ok(value.into());
+    }
+    if let Ok(value) = n.parse::<u64>() {
+        return Ok(value.into());
+    }
+    // a number that is not an integer is only converted if a float keeps its value
+    n.parse::<f64>()
+        .ok()
+        .filter(|value| same_number(n, &value.to_string()))
+        .and_then(serde_json::Number::from_f64)
+        .map(serde_json::Value::Number)
+        .ok_or_else(|| Error {
+            message: format!("number {} can't be represented exactly in JSON", n),
+        })
+}
+
+/// Convert an `AttributeValue` to JSON.
+///
+/// Values are mapped the way the deserializer reads them: `S` to strings, `N` to
+/// numbers, `BOOL` to booleans, `NULL` to null, `M` to objects, `L` and the sets
+/// to arrays, and binary values to arrays of bytes.
+pub fn to_json_value(value: &AttributeValue) -> Result<serde_json::Value> {
+    if let Some(b) = &value.b {
+        Ok(b.iter()
+            .map(|byte| serde_json::Value::from(*byte))
+            .collect())
+    } else if let Some(bool) = value.bool {
+        Ok(bool.into())
+    } else if let Some(l) = &value.l {
+        l.iter().map(to_json_value).collect()
+    } else if let Some(m) = &value.m {
+        m.iter()
+            .map(|(name, value)| Ok((name.clone(), to_json_value(value)?)))
+            .collect()
+    } else if let Some(n) = &value.n {
+        to_json_number(n)
+    } else if let Some(ns) = &value.ns {
+        ns.iter().map(|n| to_json_number(n)).collect()
+    } else if value.null.is_some() {
+        Ok(serde_json::Value::Null)
+    } else if let Some(s) = &value.s {
+        Ok(s.clone().into())
+    } else if let Some(ss) = &value.ss {
+        Ok(ss.iter().cloned().collect())
+    } else if let Some(bs) = &value.bs {
+        bs.iter()
+            .map(|b| {
+                to_json_value(&AttributeValue {
+                    b: Some(b.clone()),
+                    ..Default::default()
+                })
+            })
+            .collect()
+    } else {
+        Err(Error {
+            message: "empty attribute value".to_owned(),
+        })
+    }
+}
+
+fn json_pointer(path: &AttributePath) -> String {
+    path.elements
+        .iter()
+        .map(|element| match element {
+            PathElement::Attribute(name) => {
+                format!("/{}", name.replace('~', "~0").replace('/', "~1"))
+            }
+            PathElement::Index(index) => format!("/{}", index),
+        })
+        .collect()
+}
+
+/// The list an element was removed from, if `change` is such a removal.
+fn removed_from_list(change: &Change) -> Option<&[PathElement]> {
+    match change {
+        Change::Removed { path, .. } => match path.elements.split_last() {
+            Some((PathElement::Index(_), list)) => Some(list),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Compute the JSON Patch turning the JSON form of `old` into the JSON form of `new`.
+///
+/// The patch is built from [`diff_items`](fn.diff_items.html), with values
+/// converted by [`to_json_value`](fn.to_json_value.html). As sets become arrays
+/// with no meaningful order, a set with members added or removed is replaced.
+///
+/// # Errors
+///
+/// This can fail if a value can't be converted to JSON, for example an invalid number.
+pub fn json_patch<S: ::std::hash::BuildHasher>(
+    old: &HashMap<String, AttributeValue, S>,
+    new: &HashMap<String, AttributeValue, S>,
+) -> Result<Vec<PatchOperation>> {
+    let changes = diff_items(old, new);
+    let mut operations: Vec<PatchOperation> = vec![];
+
+    let mut i = 0;
+    while i < changes.len() {
+        if let Some(list) = removed_from_list(&changes[i]) {
+            // elements are removed from the last one so that indexes stay valid
+            let end = changes[i..]
+                .iter()
+                .position(|change| removed_from_list(change) != Some(list))
+                .map_or(changes.len(), |len| i + len);
+            operations.extend(
+                changes[i..end]
+                    .iter()
+                    .rev()
+                    .map(|change| PatchOperation::Remove {
+                        path: json_pointer(change.path()),
+                    }),
+            );
+            i = end;
+            continue;
+        }
+
+        let path = json_pointer(changes[i].path());
+        match &changes[i] {
+            Change::Added { value, .. } => operations.push(PatchOperation::Add {
+                path,
+                value: to_json_value(value)?,
+            }),
+            Change::Removed { .. } => operations.push(PatchOperation::Remove { path }),
+            Change::Changed { new, .. } => operations.push(PatchOperation::Replace {
+                path,
+                value: to_json_value(new)?,
+            }),
+            Change::MembersAdded { path: set, .. } | Change::MembersRemoved { path: set, .. } => {
+                if operations.last().map(PatchOperation::path) != Some(path.as_str()) {
+                    let value = get_path(new, set).ok_or_else(|| Error {
+                        message: format!("missing set {}", set),
+                    })?;
+                    operations.push(PatchOperation::Replace {
+                        path,
+                        value: to_json_value(value)?,
+                    });
+                }
+            }
+        }
+        i += 1;
+    }
+
+    Ok(operations)
+}
@@ -1,4 +1,7 @@
 // generated file, see update_streams.sh
 
 pub mod de;
+pub mod diff;
+#[cfg(feature = "json-patch")]
+pub mod json_patch;
 pub mod ser;
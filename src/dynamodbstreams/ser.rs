@@ -31,7 +31,7 @@ trait WriterTrait {
     fn is_in_object(&self) -> bool;
     fn insert_value(&mut self, value: AttributeValue);
 }
-impl<'a> WriterTrait for &'a mut HashMapWriter {
+impl WriterTrait for &mut HashMapWriter {
     fn set_key(&mut self, key: String) {
         self.current_key = key;
     }
@@ -48,7 +48,7 @@ struct VecWriter {
     list: Vec<AttributeValue>,
 }
 
-impl<'a> WriterTrait for &'a mut VecWriter {
+impl WriterTrait for &mut VecWriter {
     fn set_key(&mut self, _key: String) {}
     fn is_in_object(&self) -> bool {
         true
@@ -150,16 +150,25 @@ where
         Ok(())
     }
 
-    fn serialize_newtype_struct<T: ?Sized>(self, _name: &'static str, value: &T) -> Result<()>
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<()>
     where
-        T: serde::ser::Serialize,
+        T: ?Sized + serde::ser::Serialize,
     {
+        if name == crate::number::TOKEN {
+            let mut number = SimpleKeySerializer::new();
+            value.serialize(&mut number)?;
+            self.writer.insert_value(AttributeValue {
+                n: Some(number.get_result()?),
+                ..Default::default()
+            });
+            return Ok(());
+        }
         value.serialize(self)?;
         Ok(())
     }
 
     #[inline]
-    fn serialize_newtype_variant<T: ?Sized>(
+    fn serialize_newtype_variant<T>(
         self,
         _name: &'static str,
         _variant_index: u32,
@@ -167,7 +176,7 @@ where
         value: &T,
     ) -> Result<()>
     where
-        T: serde::ser::Serialize,
+        T: ?Sized + serde::ser::Serialize,
     {
         use serde::ser::SerializeTupleVariant;
         let mut compound = EnumCompound::new(self, variant, true);
@@ -183,9 +192,9 @@ where
         Ok(())
     }
 
-    fn serialize_some<T: ?Sized>(self, value: &T) -> Result<()>
+    fn serialize_some<T>(self, value: &T) -> Result<()>
     where
-        T: serde::ser::Serialize,
+        T: ?Sized + serde::ser::Serialize,
     {
         value.serialize(self)
     }
@@ -258,9 +267,9 @@ where
     type Ok = ();
     type Error = Error;
 
-    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<()>
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
     where
-        T: serde::ser::Serialize,
+        T: ?Sized + serde::ser::Serialize,
     {
         let mut ser = Serializer::new(&mut self.current);
         value.serialize(&mut ser)
@@ -278,7 +287,7 @@ where
 #[derive(Debug)]
 enum Key {
     Index(usize),
-    Key(String),
+    Name(String),
     None,
 }
 
@@ -357,9 +366,9 @@ where
     type Ok = ();
     type Error = Error;
 
-    fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<()>
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
     where
-        T: serde::ser::Serialize,
+        T: ?Sized + serde::ser::Serialize,
     {
         if let Key::None = self.current_item {
             self.current_item = Key::Index(0);
@@ -414,9 +423,9 @@ where
     type Ok = ();
     type Error = Error;
 
-    fn serialize_field<T: ?Sized>(&mut self, key: &'static str, value: &T) -> Result<()>
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
     where
-        T: serde::ser::Serialize,
+        T: ?Sized + serde::ser::Serialize,
     {
         if self.is_root {
             self.ser.writer.set_key(String::from(key));
@@ -484,9 +493,9 @@ where
     type Ok = ();
     type Error = Error;
 
-    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<()>
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
     where
-        T: serde::ser::Serialize,
+        T: ?Sized + serde::ser::Serialize,
     {
         if let Key::None = self.current_item {
             self.current_item = Key::Index(0);
@@ -530,9 +539,9 @@ where
     type Ok = ();
     type Error = Error;
 
-    fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<()>
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
     where
-        T: serde::ser::Serialize,
+        T: ?Sized + serde::ser::Serialize,
     {
         if let Key::None = self.current_item {
             self.current_item = Key::Index(0);
@@ -575,21 +584,21 @@ where
     type Ok = ();
     type Error = Error;
 
-    fn serialize_key<T: ?Sized>(&mut self, key: &T) -> Result<()>
+    fn serialize_key<T>(&mut self, key: &T) -> Result<()>
     where
-        T: serde::ser::Serialize,
+        T: ?Sized + serde::ser::Serialize,
     {
         let mut serializer = SimpleKeySerializer::new();
         key.serialize(&mut serializer)?;
-        self.current_item = Key::Key(serializer.get_result());
+        self.current_item = Key::Name(serializer.get_result()?);
         Ok(())
     }
 
-    fn serialize_value<T: ?Sized>(&mut self, value: &T) -> Result<()>
+    fn serialize_value<T>(&mut self, value: &T) -> Result<()>
     where
-        T: serde::ser::Serialize,
+        T: ?Sized + serde::ser::Serialize,
     {
-        if let Key::Key(key) = &self.current_item {
+        if let Key::Name(key) = &self.current_item {
             if self.is_root {
                 self.ser.writer.set_key(key.clone());
                 value.serialize(&mut *self.ser)?;
@@ -625,9 +634,9 @@ where
     type Ok = ();
     type Error = Error;
 
-    fn serialize_field<T: ?Sized>(&mut self, key: &'static str, value: &T) -> Result<()>
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
     where
-        T: serde::ser::Serialize,
+        T: ?Sized + serde::ser::Serialize,
     {
         if self.is_root {
             self.ser.writer.set_key(key.to_string());
@@ -650,9 +659,9 @@ where
     }
 }
 
-fn to_writer<T: ?Sized>(writer: &mut HashMapWriter, value: &T) -> Result<()>
+fn to_writer<T>(writer: &mut HashMapWriter, value: &T) -> Result<()>
 where
-    T: serde::ser::Serialize,
+    T: ?Sized + serde::ser::Serialize,
 {
     let mut ser = Serializer::new(writer);
     value.serialize(&mut ser)?;
@@ -665,9 +674,9 @@ where
 ///
 /// Serialization can fail if `T`'s implementation of `Serialize` decides to
 /// fail, or if `T` contains a map with non-string keys.
-pub fn to_hashmap<T: ?Sized>(value: &T) -> Result<HashMap<String, AttributeValue>>
+pub fn to_hashmap<T>(value: &T) -> Result<HashMap<String, AttributeValue>>
 where
-    T: serde::ser::Serialize,
+    T: ?Sized + serde::ser::Serialize,
 {
     let mut writer = HashMapWriter {
         root: HashMap::new(),
@@ -676,3 +685,23 @@ where
     to_writer(&mut writer, value)?;
     Ok(writer.root)
 }
+
+/// Serialize the given data structure as a single `AttributeValue`.
+///
+/// This is how a value is serialized as a field of a struct, for example when
+/// it's used in an expression.
+///
+/// # Errors
+///
+/// Serialization can fail if `T`'s implementation of `Serialize` decides to
+/// fail, or if `T` contains a map with non-string keys.
+pub fn to_attribute_value<T>(value: &T) -> Result<AttributeValue>
+where
+    T: ?Sized + serde::ser::Serialize,
+{
+    let mut writer = VecWriter { list: Vec::new() };
+    value.serialize(&mut Serializer::new(&mut writer))?;
+    writer.list.pop().ok_or_else(|| Error {
+        message: "no value was serialized".to_owned(),
+    })
+}
@@ -37,3 +37,44 @@ impl std::error::Error for Error {
         None
     }
 }
+
+/// An error of a request to DynamoDB made on behalf of the caller, either from
+/// DynamoDB itself or while converting the items of the request or of its response.
+#[cfg(feature = "rusoto_dynamodb")]
+#[derive(Debug)]
+pub enum RequestError<E> {
+    /// An item couldn't be serialized or deserialized.
+    Serde(Error),
+    /// The request to DynamoDB failed.
+    Rusoto(rusoto_core::RusotoError<E>),
+}
+#[cfg(feature = "rusoto_dynamodb")]
+impl<E> From<Error> for RequestError<E> {
+    fn from(error: Error) -> Self {
+        RequestError::Serde(error)
+    }
+}
+#[cfg(feature = "rusoto_dynamodb")]
+impl<E> From<rusoto_core::RusotoError<E>> for RequestError<E> {
+    fn from(error: rusoto_core::RusotoError<E>) -> Self {
+        RequestError::Rusoto(error)
+    }
+}
+#[cfg(feature = "rusoto_dynamodb")]
+impl<E: std::error::Error + 'static> std::fmt::Display for RequestError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            RequestError::Serde(error) => std::fmt::Display::fmt(error, f),
+            RequestError::Rusoto(error) => std::fmt::Display::fmt(error, f),
+        }
+    }
+}
+#[cfg(feature = "rusoto_dynamodb")]
+impl<E: std::error::Error + 'static> std::error::Error for RequestError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RequestError::Serde(error) => Some(error),
+            RequestError::Rusoto(error) => Some(error),
+        }
+    }
+}
@@ -0,0 +1,343 @@
+use rusoto_dynamodb::{AttributeValue, QueryInput};
+
+use super::ExpressionAttributes;
+use crate::dynamodb::ser::to_attribute_value;
+use crate::error::{Error, Result};
+use crate::path::AttributePath;
+
+#[derive(Debug)]
+enum Operand {
+    Path(AttributePath),
+    Size(AttributePath),
+    Value(Box<Result<AttributeValue>>),
+}
+
+impl Operand {
+    fn value<T>(value: &T) -> Self
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        Operand::Value(Box::new(to_attribute_value(value)))
+    }
+
+    fn build(self, attributes: &mut ExpressionAttributes) -> Result<String> {
+        Ok(match self {
+            Operand::Path(path) => attributes.path(&path),
+            Operand::Size(path) => format!("size ({})", attributes.path(&path)),
+            Operand::Value(value) => attributes.value((*value)?),
+        })
+    }
+}
+
+#[derive(Debug)]
+enum Kind {
+    Comparison(Operand, &'static str, Operand),
+    Between(Operand, Operand, Operand),
+    In(Operand, Vec<Operand>),
+    Function(&'static str, AttributePath, Option<Operand>),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+    Not(Box<Condition>),
+}
+
+/// A condition on the attributes of an item, to use as a `KeyConditionExpression`,
+/// a `FilterExpression` or a `ConditionExpression`.
+///
+/// Values are serialized the way [`to_hashmap`](../fn.to_hashmap.html) serializes
+/// fields, and are only checked when the condition is built.
+///
+/// ```rust
+/// use serde_dynamodb::expression::{query_input, Condition};
+///
+/// # fn main() -> Result<(), serde_dynamodb::Error> {
+/// let input = query_input(
+///     String::from("orders"),
+///     Condition::eq("customer", "42").and(Condition::begins_with("sk", "ORDER#")),
+///     Some(Condition::size("items").gt(&3)),
+/// )?;
+/// assert_eq!(
+///     input.key_condition_expression.as_deref(),
+///     Some("customer = :v0 AND begins_with (sk, :v1)")
+/// );
+/// assert_eq!(input.filter_expression.as_deref(), Some("size (#n0) > :v2"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Condition {
+    kind: Kind,
+}
+
+/// The size of an attribute, to compare in a [`Condition`](struct.Condition.html).
+#[derive(Debug)]
+pub struct Size {
+    path: AttributePath,
+}
+
+macro_rules! impl_comparison {
+    ($method:ident, $operator:expr, $doc:expr) => {
+        #[doc = $doc]
+        pub fn $method<P, T>(path: P, value: &T) -> Self
+        where
+            P: Into<AttributePath>,
+            T: ?Sized + serde::Serialize,
+        {
+            Condition::comparison(Operand::Path(path.into()), $operator, Operand::value(value))
+        }
+    };
+}
+
+macro_rules! impl_size_comparison {
+    ($method:ident, $operator:expr, $doc:expr) => {
+        #[doc = $doc]
+        pub fn $method<T>(self, value: &T) -> Condition
+        where
+            T: ?Sized + serde::Serialize,
+        {
+            Condition::comparison(Operand::Size(self.path), $operator, Operand::value(value))
+        }
+    };
+}
+
+impl Size {
+    impl_size_comparison!(eq, "=", "The size is equal to `value`.");
+    impl_size_comparison!(ne, "<>", "The size is not equal to `value`.");
+    impl_size_comparison!(lt, "<", "The size is less than `value`.");
+    impl_size_comparison!(le, "<=", "The size is less than or equal to `value`.");
+    impl_size_comparison!(gt, ">", "The size is greater than `value`.");
+    impl_size_comparison!(ge, ">=", "The size is greater than or equal to `value`.");
+
+    /// The size is between `low` and `high`, inclusive.
+    pub fn between<T>(self, low: &T, high: &T) -> Condition
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        Condition {
+            kind: Kind::Between(
+                Operand::Size(self.path),
+                Operand::value(low),
+                Operand::value(high),
+            ),
+        }
+    }
+}
+
+impl Condition {
+    fn comparison(left: Operand, operator: &'static str, right: Operand) -> Self {
+        Condition {
+            kind: Kind::Comparison(left, operator, right),
+        }
+    }
+
+    fn function(name: &'static str, path: AttributePath, operand: Option<Operand>) -> Self {
+        Condition {
+            kind: Kind::Function(name, path, operand),
+        }
+    }
+
+    impl_comparison!(eq, "=", "The attribute at `path` is equal to `value`.");
+    impl_comparison!(ne, "<>", "The attribute at `path` is not equal to `value`.");
+    impl_comparison!(lt, "<", "The attribute at `path` is less than `value`.");
+    impl_comparison!(
+        le,
+        "<=",
+        "The attribute at `path` is less than or equal to `value`."
+    );
+    impl_comparison!(gt, ">", "The attribute at `path` is greater than `value`.");
+    impl_comparison!(
+        ge,
+        ">=",
+        "The attribute at `path` is greater than or equal to `value`."
+    );
+
+    /// The attribute at `path` is between `low` and `high`, inclusive.
+    pub fn between<P, T>(path: P, low: &T, high: &T) -> Self
+    where
+        P: Into<AttributePath>,
+        T: ?Sized + serde::Serialize,
+    {
+        Condition {
+            kind: Kind::Between(
+                Operand::Path(path.into()),
+                Operand::value(low),
+                Operand::value(high),
+            ),
+        }
+    }
+
+    /// The attribute at `path` is equal to one of `values`, which must not be empty.
+    pub fn is_in<P, I>(path: P, values: I) -> Self
+    where
+        P: Into<AttributePath>,
+        I: IntoIterator,
+        I::Item: serde::Serialize,
+    {
+        Condition {
+            kind: Kind::In(
+                Operand::Path(path.into()),
+                values
+                    .into_iter()
+                    .map(|value| Operand::value(&value))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// The string attribute at `path` starts with `prefix`.
+    pub fn begins_with<P: Into<AttributePath>>(path: P, prefix: &str) -> Self {
+        Condition::function("begins_with", path.into(), Some(Operand::value(prefix)))
+    }
+
+    /// The attribute at `path` contains `value`, as a substring of a string or as
+    /// an element of a set or a list.
+    pub fn contains<P, T>(path: P, value: &T) -> Self
+    where
+        P: Into<AttributePath>,
+        T: ?Sized + serde::Serialize,
+    {
+        Condition::function("contains", path.into(), Some(Operand::value(value)))
+    }
+
+    /// The item has an attribute at `path`.
+    pub fn attribute_exists<P: Into<AttributePath>>(path: P) -> Self {
+        Condition::function("attribute_exists", path.into(), None)
+    }
+
+    /// The item doesn't have an attribute at `path`.
+    pub fn attribute_not_exists<P: Into<AttributePath>>(path: P) -> Self {
+        Condition::function("attribute_not_exists", path.into(), None)
+    }
+
+    /// The size of the attribute at `path`, to compare to a value.
+    pub fn size<P: Into<AttributePath>>(path: P) -> Size {
+        Size { path: path.into() }
+    }
+
+    /// All of `conditions` are true, or `None` if there are no conditions.
+    pub fn all<I: IntoIterator<Item = Condition>>(conditions: I) -> Option<Self> {
+        conditions.into_iter().reduce(Condition::and)
+    }
+
+    /// Both this condition and `other` are true.
+    pub fn and(self, other: Condition) -> Self {
+        Condition {
+            kind: Kind::And(Box::new(self), Box::new(other)),
+        }
+    }
+
+    /// This condition or `other` is true.
+    pub fn or(self, other: Condition) -> Self {
+        Condition {
+            kind: Kind::Or(Box::new(self), Box::new(other)),
+        }
+    }
+
+    /// Build this condition as an operand of `operator`, with parentheses if needed.
+    fn build_operand(
+        self,
+        operator: &str,
+        attributes: &mut ExpressionAttributes,
+    ) -> Result<String> {
+        let needs_parentheses = match self.kind {
+            Kind::And(..) => operator != "AND",
+            Kind::Or(..) => operator != "OR",
+            _ => false,
+        };
+        let expression = self.build(attributes)?;
+        Ok(if needs_parentheses {
+            format!("({})", expression)
+        } else {
+            expression
+        })
+    }
+
+    /// Build the expression of this condition, adding its placeholders to `attributes`.
+    ///
+    /// # Errors
+    ///
+    /// This fails if one of the values of the condition couldn't be serialized, or
+    /// if an `IN` condition has no values.
+    pub fn build(self, attributes: &mut ExpressionAttributes) -> Result<String> {
+        Ok(match self.kind {
+            Kind::Comparison(left, operator, right) => format!(
+                "{} {} {}",
+                left.build(attributes)?,
+                operator,
+                right.build(attributes)?
+            ),
+            Kind::Between(operand, low, high) => format!(
+                "{} BETWEEN {} AND {}",
+                operand.build(attributes)?,
+                low.build(attributes)?,
+                high.build(attributes)?
+            ),
+            Kind::In(operand, values) => {
+                if values.is_empty() {
+                    return Err(Error {
+                        message: String::from("an IN condition needs at least one value"),
+                    });
+                }
+                let operand = operand.build(attributes)?;
+                let values = values
+                    .into_iter()
+                    .map(|value| value.build(attributes))
+                    .collect::<Result<Vec<_>>>()?;
+                format!("{} IN ({})", operand, values.join(", "))
+            }
+            Kind::Function(name, path, None) => format!("{} ({})", name, attributes.path(&path)),
+            Kind::Function(name, path, Some(operand)) => {
+                let path = attributes.path(&path);
+                format!("{} ({}, {})", name, path, operand.build(attributes)?)
+            }
+            Kind::And(left, right) => format!(
+                "{} AND {}",
+                left.build_operand("AND", attributes)?,
+                right.build_operand("AND", attributes)?
+            ),
+            Kind::Or(left, right) => format!(
+                "{} OR {}",
+                left.build_operand("OR", attributes)?,
+                right.build_operand("OR", attributes)?
+            ),
+            Kind::Not(condition) => format!("NOT ({})", condition.build(attributes)?),
+        })
+    }
+}
+
+impl std::ops::Not for Condition {
+    type Output = Condition;
+
+    /// This condition is false.
+    fn not(self) -> Self::Output {
+        Condition {
+            kind: Kind::Not(Box::new(self)),
+        }
+    }
+}
+
+/// A `QueryInput` on `table_name` with the given key condition and filter.
+///
+/// Both expressions share the same expression attributes.
+///
+/// # Errors
+///
+/// This fails if one of the values of the conditions couldn't be serialized.
+pub fn query_input(
+    table_name: String,
+    key_condition: Condition,
+    filter: Option<Condition>,
+) -> Result<QueryInput> {
+    let mut attributes = ExpressionAttributes::new();
+    let key_condition_expression = key_condition.build(&mut attributes)?;
+    let filter_expression = filter
+        .map(|filter| filter.build(&mut attributes))
+        .transpose()?;
+    Ok(QueryInput {
+        table_name,
+        key_condition_expression: Some(key_condition_expression),
+        filter_expression,
+        expression_attribute_names: attributes.names(),
+        expression_attribute_values: attributes.values(),
+        ..Default::default()
+    })
+}
@@ -0,0 +1,102 @@
+//! Build DynamoDB expressions from Rust data structures.
+//!
+//! Expressions refer to values through placeholders, which are collected in
+//! [`ExpressionAttributes`](struct.ExpressionAttributes.html) so that they can
+//! be set as `ExpressionAttributeValues` of a request. Attribute names are used
+//! as is, unless they are [reserved words](constant.RESERVED_WORDS.html) or
+//! contain characters such as dots or dashes, in which case they are replaced
+//! by placeholders set as `ExpressionAttributeNames`.
+
+use std::collections::HashMap;
+
+use rusoto_dynamodb::AttributeValue;
+
+use crate::path::{AttributePath, PathElement};
+
+mod condition;
+mod projection;
+mod reserved;
+mod update;
+
+pub use self::condition::{query_input, Condition, Size};
+pub use self::projection::{projection_for, Projection};
+pub use self::reserved::{escape_attribute_path, is_reserved_word, RESERVED_WORDS};
+pub use self::update::Update;
+
+/// The attribute names and values used by expressions, by placeholder.
+///
+/// Several expressions of the same request must share their `ExpressionAttributes`
+/// so that their placeholders don't collide.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ExpressionAttributes {
+    /// The attribute names, by placeholder such as `#n0`.
+    pub names: HashMap<String, String>,
+    /// The attribute values, by placeholder such as `:v0`.
+    pub values: HashMap<String, AttributeValue>,
+}
+
+impl ExpressionAttributes {
+    /// Create an empty set of expression attributes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The placeholder for the attribute name `name`.
+    ///
+    /// Unlike [`path`](#method.path), this always uses a placeholder.
+    pub fn name(&mut self, name: &str) -> String {
+        if let Some((placeholder, _)) = self.names.iter().find(|(_, known)| *known == name) {
+            return placeholder.clone();
+        }
+        let placeholder = format!("#n{}", self.names.len());
+        self.names.insert(placeholder.clone(), name.to_owned());
+        placeholder
+    }
+
+    /// The document path to `path`, with a placeholder for each attribute name
+    /// that needs one.
+    pub fn path(&mut self, path: &AttributePath) -> String {
+        let mut expression = String::new();
+        for element in &path.elements {
+            match element {
+                PathElement::Attribute(name) => {
+                    if !expression.is_empty() {
+                        expression.push('.');
+                    }
+                    if reserved::needs_placeholder(name) {
+                        expression.push_str(&self.name(name));
+                    } else {
+                        expression.push_str(name);
+                    }
+                }
+                PathElement::Index(index) => expression.push_str(&format!("[{}]", index)),
+            }
+        }
+        expression
+    }
+
+    /// The placeholder for a new value.
+    pub fn value(&mut self, value: AttributeValue) -> String {
+        let placeholder = format!(":v{}", self.values.len());
+        self.values.insert(placeholder.clone(), value);
+        placeholder
+    }
+
+    /// The attribute names, to set as `ExpressionAttributeNames` of a request.
+    pub fn names(&self) -> Option<HashMap<String, String>> {
+        if self.names.is_empty() {
+            None
+        } else {
+            Some(self.names.clone())
+        }
+    }
+
+    /// The attribute values, to set as `ExpressionAttributeValues` of a request.
+    pub fn values(&self) -> Option<HashMap<String, AttributeValue>> {
+        if self.values.is_empty() {
+            None
+        } else {
+            Some(self.values.clone())
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+
+use serde::de::DeserializeOwned;
+
+use super::ExpressionAttributes;
+use crate::error::{Error, Result};
+use crate::path::AttributePath;
+use crate::trace::trace;
+
+/// The attributes to read from an item, as a `ProjectionExpression`.
+///
+/// ```rust
+/// use serde::Deserialize;
+/// use serde_dynamodb::expression::projection_for;
+///
+/// #[derive(Deserialize)]
+/// struct Address {
+///     city: String,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Summary {
+///     id: String,
+///     #[serde(rename = "name")]
+///     full_name: String,
+///     address: Option<Address>,
+/// }
+///
+/// # fn main() -> Result<(), serde_dynamodb::Error> {
+/// let (projection_expression, names) = projection_for::<Summary>()?.into_expression();
+/// assert_eq!(projection_expression, "id, #n0, address.city");
+/// assert_eq!(names["#n0"], "name");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Projection {
+    /// The paths of the attributes to read.
+    pub paths: Vec<AttributePath>,
+}
+
+impl Projection {
+    /// Build the projection expression, adding its placeholders to `attributes`.
+    pub fn build(&self, attributes: &mut ExpressionAttributes) -> String {
+        self.paths
+            .iter()
+            .map(|path| attributes.path(path))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// The projection expression and its `ExpressionAttributeNames`, when it is the
+    /// only expression of a request.
+    pub fn into_expression(self) -> (String, HashMap<String, String>) {
+        let mut attributes = ExpressionAttributes::new();
+        let expression = self.build(&mut attributes);
+        (expression, attributes.names)
+    }
+}
+
+/// The attributes read by the `Deserialize` implementation of `T`.
+///
+/// The fields of `T` are traced through the `fields` given to `deserialize_struct`,
+/// so serde renames are honored. Fields that are structs, optional or not, are
+/// replaced by the paths to their own fields. Other fields, including lists and
+/// maps, are read as a whole.
+///
+/// # Errors
+///
+/// This fails if `T` is not deserialized as a struct, which is also the case of a
+/// struct with flattened fields.
+pub fn projection_for<T: DeserializeOwned>() -> Result<Projection> {
+    let (trace, result) = trace::<T>();
+    if trace.leaves.is_empty() {
+        return Err(result.err().unwrap_or_else(|| Error {
+            message: "no field to project".to_owned(),
+        }));
+    }
+    // a field that can't be traced is read as a whole, which is all we need
+    Ok(Projection {
+        paths: trace.leaves,
+    })
+}
@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+
+use rusoto_dynamodb::{AttributeValue, UpdateItemInput};
+
+use super::ExpressionAttributes;
+use crate::dynamodb::diff::{diff_items, get_path, Change};
+use crate::dynamodb::ser::to_hashmap;
+use crate::error::Result;
+use crate::path::{AttributePath, PathElement};
+
+/// An update of an item, as an `UpdateExpression` and the attributes it uses.
+///
+/// ```rust
+/// use serde::Serialize;
+/// use serde_dynamodb::expression::Update;
+///
+/// #[derive(Serialize)]
+/// struct Task {
+///     id: String,
+///     status: String,
+///     done: bool,
+/// }
+///
+/// # fn main() -> Result<(), serde_dynamodb::Error> {
+/// let before = Task { id: String::from("1"), status: String::from("todo"), done: false };
+/// let after = Task { id: String::from("1"), status: String::from("done"), done: true };
+///
+/// let input = rusoto_dynamodb::UpdateItemInput {
+///     table_name: String::from("tasks"),
+///     key: serde_dynamodb::to_hashmap(&before)?
+///         .into_iter()
+///         .filter(|(name, _)| name == "id")
+///         .collect(),
+///     ..Update::between(&before, &after, &["id"])?.into_update_item_input()
+/// };
+/// assert_eq!(input.update_expression.as_deref(), Some("SET done = :v0, #n0 = :v1"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Update {
+    /// The update expression, `None` if there is nothing to update.
+    pub update_expression: Option<String>,
+    /// The attribute names and values used by the update expression.
+    pub attributes: ExpressionAttributes,
+}
+
+fn is_key(path: &AttributePath, key_attributes: &[&str]) -> bool {
+    match path.elements.first() {
+        Some(PathElement::Attribute(name)) => key_attributes.contains(&name.as_str()),
+        _ => false,
+    }
+}
+
+impl Update {
+    /// The update turning the serialized form of `before` into the serialized form of `after`.
+    ///
+    /// Key attributes can't be updated, so changes to `key_attributes` are ignored.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if `before` or `after` can't be serialized.
+    pub fn between<T>(before: &T, after: &T, key_attributes: &[&str]) -> Result<Self>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        let before = to_hashmap(before)?;
+        let after = to_hashmap(after)?;
+        Ok(Update::from_changes(
+            &diff_items(&before, &after),
+            &after,
+            key_attributes,
+        ))
+    }
+
+    /// The update applying `changes`, that lead to the item `new`.
+    ///
+    /// Attributes that are added or changed are `SET`, attributes that are removed
+    /// are `REMOVE`d, members added to a set are `ADD`ed and members removed from a
+    /// set are `DELETE`d. As a set can't appear in both `ADD` and `DELETE`, a set
+    /// with members both added and removed is `SET` to its value in `new`.
+    pub fn from_changes<S: ::std::hash::BuildHasher>(
+        changes: &[Change],
+        new: &HashMap<String, AttributeValue, S>,
+        key_attributes: &[&str],
+    ) -> Self {
+        let mut attributes = ExpressionAttributes::new();
+        let mut set = vec![];
+        let mut remove = vec![];
+        let mut add = vec![];
+        let mut delete = vec![];
+
+        let replaced_sets: Vec<&AttributePath> = changes
+            .iter()
+            .filter_map(|change| match change {
+                Change::MembersAdded { path, .. } => Some(path),
+                _ => None,
+            })
+            .filter(|added| {
+                changes.iter().any(|change| match change {
+                    Change::MembersRemoved { path, .. } => path == *added,
+                    _ => false,
+                })
+            })
+            .collect();
+
+        for change in changes {
+            if is_key(change.path(), key_attributes) {
+                continue;
+            }
+            match change {
+                Change::Added { path, value }
+                | Change::Changed {
+                    path, new: value, ..
+                } => {
+                    let path = attributes.path(path);
+                    let value = attributes.value(value.clone());
+                    set.push(format!("{} = {}", path, value));
+                }
+                Change::Removed { path, .. } => remove.push(attributes.path(path)),
+                Change::MembersAdded { path, .. } | Change::MembersRemoved { path, .. }
+                    if replaced_sets.contains(&path) =>
+                {
+                    if let (Change::MembersAdded { .. }, Some(value)) =
+                        (change, get_path(new, path))
+                    {
+                        let path = attributes.path(path);
+                        let value = attributes.value(value.clone());
+                        set.push(format!("{} = {}", path, value));
+                    }
+                }
+                Change::MembersAdded { path, members } => {
+                    let path = attributes.path(path);
+                    let members = attributes.value(members.clone());
+                    add.push(format!("{} {}", path, members));
+                }
+                Change::MembersRemoved { path, members } => {
+                    let path = attributes.path(path);
+                    let members = attributes.value(members.clone());
+                    delete.push(format!("{} {}", path, members));
+                }
+            }
+        }
+
+        let clauses: Vec<String> = [
+            ("SET", set),
+            ("REMOVE", remove),
+            ("ADD", add),
+            ("DELETE", delete),
+        ]
+        .iter()
+        .filter(|(_, actions)| !actions.is_empty())
+        .map(|(clause, actions)| format!("{} {}", clause, actions.join(", ")))
+        .collect();
+
+        Update {
+            update_expression: if clauses.is_empty() {
+                None
+            } else {
+                Some(clauses.join(" "))
+            },
+            attributes,
+        }
+    }
+
+    /// Whether there is nothing to update.
+    pub fn is_empty(&self) -> bool {
+        self.update_expression.is_none()
+    }
+
+    /// Whether an action of the update expression writes the attribute `name`, or
+    /// one of its members.
+    pub(crate) fn writes(&self, name: &str) -> bool {
+        let expression = match &self.update_expression {
+            Some(expression) => expression,
+            None => return false,
+        };
+        written_paths(expression).into_iter().any(|path| {
+            let attribute = path.split(['.', '[']).next().unwrap_or_default();
+            let attribute = self
+                .attributes
+                .names
+                .get(attribute)
+                .map_or(attribute, String::as_str);
+            attribute == name
+        })
+    }
+
+    /// An `UpdateItemInput` with the update expression and its attributes set.
+    ///
+    /// The table name and the key of the item to update are left empty.
+    pub fn into_update_item_input(self) -> UpdateItemInput {
+        UpdateItemInput {
+            update_expression: self.update_expression,
+            expression_attribute_names: self.attributes.names(),
+            expression_attribute_values: self.attributes.values(),
+            ..Default::default()
+        }
+    }
+}
+
+/// The paths written by the actions of an update expression, the first operand of
+/// each `SET`, `REMOVE`, `ADD` or `DELETE` action.
+fn written_paths(expression: &str) -> Vec<&str> {
+    let mut paths = vec![];
+    let mut depth = 0usize;
+    let mut expecting_path = false;
+    let mut chars = expression.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => expecting_path = true,
+            c if c.is_whitespace() || depth > 0 => {}
+            _ => {
+                let end = expression[start..]
+                    .find(|c: char| c.is_whitespace() || "=,()".contains(c))
+                    .map_or(expression.len(), |len| start + len);
+                let word = &expression[start..end];
+                if ["SET", "REMOVE", "ADD", "DELETE"]
+                    .iter()
+                    .any(|clause| clause.eq_ignore_ascii_case(word))
+                {
+                    expecting_path = true;
+                } else if expecting_path {
+                    paths.push(word);
+                    expecting_path = false;
+                }
+                while matches!(chars.peek(), Some((next, _)) if *next < end) {
+                    chars.next();
+                }
+            }
+        }
+    }
+    paths
+}
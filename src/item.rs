@@ -0,0 +1,173 @@
+//! Items of a table, with their key.
+
+use std::collections::HashMap;
+
+use rusoto_dynamodb::AttributeValue;
+use serde::de::DeserializeOwned;
+
+use crate::dynamodb::de::from_hashmap;
+use crate::error::{Error, Result};
+use crate::path::PathElement;
+use crate::trace::trace;
+
+/// The attributes copied to a secondary index.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IndexProjection {
+    /// All the attributes of the item.
+    All,
+    /// Only the keys of the table and of the index.
+    KeysOnly,
+    /// The keys and the given attributes.
+    Include(&'static [&'static str]),
+}
+
+/// A secondary index of a table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SecondaryIndex {
+    /// The name of the index.
+    pub name: &'static str,
+    /// Whether this is a local secondary index, sharing the partition key of the table.
+    pub local: bool,
+    /// The name of the partition key attribute of the index.
+    pub partition_key: &'static str,
+    /// The name of the sort key attribute of the index, if it has one.
+    pub sort_key: Option<&'static str>,
+    /// The attributes copied to the index.
+    pub projection: IndexProjection,
+}
+
+impl SecondaryIndex {
+    /// Whether the attribute `name` is copied to this index, given the key attributes
+    /// of the table, which are always copied.
+    pub fn projects(&self, name: &str, table_keys: &[&str]) -> bool {
+        name == self.partition_key
+            || self.sort_key == Some(name)
+            || table_keys.contains(&name)
+            || match self.projection {
+                IndexProjection::All => true,
+                IndexProjection::KeysOnly => false,
+                IndexProjection::Include(attributes) => attributes.contains(&name),
+            }
+    }
+}
+
+/// How reads and writes on a table are paid for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BillingMode {
+    /// On demand capacity.
+    PayPerRequest,
+    /// Provisioned capacity, for the table and each of its global secondary indexes.
+    Provisioned {
+        /// The read capacity units.
+        read_capacity_units: i64,
+        /// The write capacity units.
+        write_capacity_units: i64,
+    },
+}
+
+/// A data structure stored as an item of a DynamoDB table.
+///
+/// It can be derived with `#[derive(DynamoItem)]` from the `serde_dynamodb_derive`
+/// crate, which also generates the `Key` struct.
+pub trait DynamoItem {
+    /// The name of the table.
+    const TABLE_NAME: &'static str;
+    /// The name of the partition key attribute.
+    const PARTITION_KEY: &'static str;
+    /// The name of the sort key attribute, if the table has one.
+    const SORT_KEY: Option<&'static str>;
+    /// The secondary indexes of the table.
+    const INDEXES: &'static [SecondaryIndex] = &[];
+    /// How reads and writes on the table are paid for.
+    const BILLING_MODE: BillingMode = BillingMode::PayPerRequest;
+    /// The `StreamViewType` of the stream of the table, if it has one.
+    const STREAM_VIEW_TYPE: Option<&'static str> = None;
+    /// The name of the attribute used as time to live, if there is one.
+    const TTL_ATTRIBUTE: Option<&'static str> = None;
+    /// The name of the attribute holding the version of the item, if there is one.
+    ///
+    /// A [`Table`](struct.Table.html) only writes an item if its version is still
+    /// the one it was read with, and increments it.
+    const VERSION_ATTRIBUTE: Option<&'static str> = None;
+    /// The name of the attribute set to the time the item was created, if there is
+    /// one.
+    const CREATED_AT_ATTRIBUTE: Option<&'static str> = None;
+    /// The name of the attribute set to the time the item was last written, if
+    /// there is one.
+    const UPDATED_AT_ATTRIBUTE: Option<&'static str> = None;
+
+    /// The key attributes of an item, as a struct. It serializes with
+    /// [`to_hashmap`](fn.to_hashmap.html) to the same value as
+    /// [`try_key`](#tymethod.try_key).
+    type Key: serde::Serialize;
+
+    /// The key of this item, to use in `GetItem`, `DeleteItem` or `UpdateItem`.
+    ///
+    /// # Errors
+    ///
+    /// This fails if a key attribute couldn't be serialized.
+    fn try_key(&self) -> Result<HashMap<String, AttributeValue>>;
+
+    /// The current time, serialized as the timestamp attribute `attribute`, one of
+    /// [`CREATED_AT_ATTRIBUTE`](#associatedconstant.CREATED_AT_ATTRIBUTE) and
+    /// [`UPDATED_AT_ATTRIBUTE`](#associatedconstant.UPDATED_AT_ATTRIBUTE).
+    ///
+    /// # Errors
+    ///
+    /// This fails if `attribute` isn't a timestamp, or if the time couldn't be
+    /// serialized.
+    fn timestamp(attribute: &str) -> Result<AttributeValue> {
+        Err(Error {
+            message: format!("{} is not a timestamp", attribute),
+        })
+    }
+}
+
+/// Deserialize an item read from the secondary index `index_name` of the table of `T`.
+///
+/// Attributes missing from an item are usually given a default value by
+/// [`from_hashmap`](fn.from_hashmap.html), as an empty string for example. When the
+/// index doesn't project all the attributes, a field of `T` whose attribute is
+/// missing from `item` and isn't copied to the index is an error instead, as its
+/// value can't be known without reading the item from the table.
+///
+/// # Errors
+///
+/// This fails if `T` has no index `index_name`, if a field of `T` isn't projected
+/// in the index and is missing from `item`, or if `item` can't be deserialized.
+pub fn from_index_item<T, S>(
+    index_name: &str,
+    item: HashMap<String, AttributeValue, S>,
+) -> Result<T>
+where
+    T: DynamoItem + DeserializeOwned,
+    S: std::hash::BuildHasher + Clone,
+{
+    let index = T::INDEXES
+        .iter()
+        .find(|index| index.name == index_name)
+        .ok_or_else(|| Error {
+            message: format!("table {} has no index {}", T::TABLE_NAME, index_name),
+        })?;
+    if index.projection != IndexProjection::All {
+        let table_keys: Vec<&str> = std::iter::once(T::PARTITION_KEY)
+            .chain(T::SORT_KEY)
+            .collect();
+        let (trace, _) = trace::<T>();
+        for path in &trace.leaves {
+            if let Some(PathElement::Attribute(name)) = path.elements.first() {
+                if !index.projects(name, &table_keys) && !item.contains_key(name) {
+                    return Err(Error {
+                        message: format!(
+                            "attribute {} is not projected in index {} of table {}",
+                            name,
+                            index_name,
+                            T::TABLE_NAME
+                        ),
+                    });
+                }
+            }
+        }
+    }
+    from_hashmap(item)
+}
@@ -0,0 +1,183 @@
+//! Decode the records a DynamoDB table writes to Kinesis Data Streams.
+//!
+//! Those records use a different envelope than DynamoDB Streams: they carry the
+//! name of the table, no sequence number nor stream view type, and their creation
+//! time is in milliseconds.
+//!
+//! ```rust
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize, Debug)]
+//! struct Key {
+//!     id: String,
+//! }
+//!
+//! #[derive(Deserialize, Debug)]
+//! struct Task {
+//!     id: String,
+//!     done: bool,
+//! }
+//!
+//! # fn main() -> Result<(), serde_dynamodb::Error> {
+//! let data = br#"{
+//!     "awsRegion": "us-east-1",
+//!     "eventID": "1",
+//!     "eventName": "REMOVE",
+//!     "userIdentity": {"type": "Service", "principalId": "dynamodb.amazonaws.com"},
+//!     "recordFormat": "application/json",
+//!     "tableName": "tasks",
+//!     "dynamodb": {
+//!         "ApproximateCreationDateTime": 1611357270000,
+//!         "Keys": {"id": {"S": "task"}},
+//!         "OldImage": {"id": {"S": "task"}, "done": {"BOOL": true}},
+//!         "SizeBytes": 24
+//!     },
+//!     "eventSource": "aws:dynamodb"
+//! }"#;
+//!
+//! let event = serde_dynamodb::kinesis::from_slice::<Key, Task>(data)?;
+//! assert!(event.removed_by_ttl);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+
+use rusoto_dynamodbstreams::{AttributeValue, Identity, Record, StreamRecord};
+use serde::Deserialize;
+
+use crate::change_event::ChangeEvent;
+use crate::error::{Error, Result};
+
+/// The identity that made a change, present for items deleted by Time to Live.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct UserIdentity {
+    /// The type of the identity, `Service` for Time to Live deletions.
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    /// The principal of the identity, `dynamodb.amazonaws.com` for Time to Live deletions.
+    #[serde(rename = "principalId")]
+    pub principal_id: Option<String>,
+}
+
+/// The DynamoDB part of a [`KinesisRecord`](struct.KinesisRecord.html).
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct KinesisStreamRecord {
+    /// The approximate date and time when the record was created, in
+    /// milliseconds or microseconds since epoch depending on its precision.
+    #[serde(rename = "ApproximateCreationDateTime")]
+    pub approximate_creation_date_time: Option<f64>,
+    /// `MILLISECOND` or `MICROSECOND`, defaults to milliseconds when absent.
+    #[serde(rename = "ApproximateCreationDateTimePrecision")]
+    pub approximate_creation_date_time_precision: Option<String>,
+    /// The key attributes of the modified item.
+    #[serde(rename = "Keys")]
+    pub keys: Option<HashMap<String, AttributeValue>>,
+    /// The item as it appears after it was modified.
+    #[serde(rename = "NewImage")]
+    pub new_image: Option<HashMap<String, AttributeValue>>,
+    /// The item as it appeared before it was modified.
+    #[serde(rename = "OldImage")]
+    pub old_image: Option<HashMap<String, AttributeValue>>,
+    /// The size of the record, in bytes.
+    #[serde(rename = "SizeBytes")]
+    pub size_bytes: Option<i64>,
+}
+
+/// A record written by DynamoDB to Kinesis Data Streams.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct KinesisRecord {
+    /// The region in which the change happened.
+    #[serde(rename = "awsRegion")]
+    pub aws_region: Option<String>,
+    /// A globally unique identifier for the event.
+    #[serde(rename = "eventID")]
+    pub event_id: Option<String>,
+    /// `INSERT`, `MODIFY` or `REMOVE`.
+    #[serde(rename = "eventName")]
+    pub event_name: Option<String>,
+    /// The identity that made the change, for Time to Live deletions.
+    #[serde(rename = "userIdentity")]
+    pub user_identity: Option<UserIdentity>,
+    /// The format of the record, `application/json`.
+    #[serde(rename = "recordFormat")]
+    pub record_format: Option<String>,
+    /// The name of the table that was modified.
+    #[serde(rename = "tableName")]
+    pub table_name: Option<String>,
+    /// The change itself.
+    #[serde(rename = "dynamodb")]
+    pub dynamodb: Option<KinesisStreamRecord>,
+    /// `aws:dynamodb`.
+    #[serde(rename = "eventSource")]
+    pub event_source: Option<String>,
+}
+
+impl KinesisRecord {
+    /// Parse a record from the data of a Kinesis record.
+    pub fn from_slice(data: &[u8]) -> Result<Self> {
+        serde_json::from_slice(data).map_err(|err| Error {
+            message: format!("invalid kinesis record: {}", err),
+        })
+    }
+
+    /// Decode this record into a typed [`ChangeEvent`][change_event].
+    ///
+    /// The creation time of the event is converted to seconds since epoch, as
+    /// for DynamoDB Streams records.
+    ///
+    /// [change_event]: ../streams/struct.ChangeEvent.html
+    pub fn into_change_event<K, T>(self) -> Result<ChangeEvent<K, T>>
+    where
+        K: serde::de::DeserializeOwned,
+        T: serde::de::DeserializeOwned,
+    {
+        let dynamodb = self.dynamodb.ok_or_else(|| Error {
+            message: "missing stream record".to_owned(),
+        })?;
+        let divider = match dynamodb.approximate_creation_date_time_precision.as_deref() {
+            None | Some("MILLISECOND") => 1_000.0,
+            Some("MICROSECOND") => 1_000_000.0,
+            Some(precision) => {
+                return Err(Error {
+                    message: format!("unknown creation date time precision {:?}", precision),
+                })
+            }
+        };
+        ChangeEvent::from_record(Record {
+            aws_region: self.aws_region,
+            dynamodb: Some(StreamRecord {
+                approximate_creation_date_time: dynamodb
+                    .approximate_creation_date_time
+                    .map(|time| time / divider),
+                keys: dynamodb.keys,
+                new_image: dynamodb.new_image,
+                old_image: dynamodb.old_image,
+                size_bytes: dynamodb.size_bytes,
+                ..Default::default()
+            }),
+            event_id: self.event_id,
+            event_name: self.event_name,
+            event_source: self.event_source,
+            user_identity: self.user_identity.map(|identity| Identity {
+                principal_id: identity.principal_id,
+                type_: identity.type_,
+            }),
+            ..Default::default()
+        })
+    }
+}
+
+/// Decode the data of a Kinesis record into a typed change event.
+///
+/// # Errors
+///
+/// This conversion can fail if the data is not a DynamoDB record, or if its keys
+/// or images can't be deserialized.
+pub fn from_slice<K, T>(data: &[u8]) -> Result<ChangeEvent<K, T>>
+where
+    K: serde::de::DeserializeOwned,
+    T: serde::de::DeserializeOwned,
+{
+    KinesisRecord::from_slice(data)?.into_change_event()
+}
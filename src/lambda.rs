@@ -0,0 +1,88 @@
+//! Parse the event payload AWS Lambda receives from a DynamoDB Streams trigger.
+//!
+//! ```rust
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize, Debug)]
+//! struct Key {
+//!     id: String,
+//! }
+//!
+//! #[derive(Deserialize, Debug)]
+//! struct Task {
+//!     id: String,
+//!     done: bool,
+//! }
+//!
+//! # fn main() -> Result<(), serde_dynamodb::Error> {
+//! let payload = r#"{"Records": [{
+//!     "eventID": "1",
+//!     "eventName": "INSERT",
+//!     "eventSource": "aws:dynamodb",
+//!     "dynamodb": {
+//!         "Keys": {"id": {"S": "task"}},
+//!         "NewImage": {"id": {"S": "task"}, "done": {"BOOL": false}},
+//!         "SequenceNumber": "111",
+//!         "StreamViewType": "NEW_AND_OLD_IMAGES"
+//!     }
+//! }]}"#;
+//!
+//! let events = serde_dynamodb::lambda::from_str::<Key, Task>(payload)?;
+//! assert_eq!(events[0].new_image.as_ref().map(|task| task.done), Some(false));
+//! # Ok(())
+//! # }
+//! ```
+
+use rusoto_dynamodbstreams::Record;
+use serde::Deserialize;
+
+use crate::change_event::ChangeEvent;
+use crate::error::{Error, Result};
+
+/// The event payload of a Lambda function triggered by DynamoDB Streams.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct LambdaEvent {
+    /// The stream records of this invocation.
+    #[serde(rename = "Records")]
+    pub records: Vec<Record>,
+}
+
+impl std::str::FromStr for LambdaEvent {
+    type Err = Error;
+
+    fn from_str(payload: &str) -> Result<Self> {
+        serde_json::from_str(payload).map_err(|err| Error {
+            message: format!("invalid lambda event: {}", err),
+        })
+    }
+}
+
+impl LambdaEvent {
+    /// Decode every record of this event into a typed [`ChangeEvent`][change_event].
+    ///
+    /// [change_event]: ../streams/struct.ChangeEvent.html
+    pub fn into_change_events<K, T>(self) -> Result<Vec<ChangeEvent<K, T>>>
+    where
+        K: serde::de::DeserializeOwned,
+        T: serde::de::DeserializeOwned,
+    {
+        self.records
+            .into_iter()
+            .map(ChangeEvent::from_record)
+            .collect()
+    }
+}
+
+/// Parse a Lambda event payload into typed change events.
+///
+/// # Errors
+///
+/// This conversion can fail if the payload is not a valid DynamoDB Streams
+/// event, or if a record can't be decoded into a `ChangeEvent<K, T>`.
+pub fn from_str<K, T>(payload: &str) -> Result<Vec<ChangeEvent<K, T>>>
+where
+    K: serde::de::DeserializeOwned,
+    T: serde::de::DeserializeOwned,
+{
+    payload.parse::<LambdaEvent>()?.into_change_events()
+}
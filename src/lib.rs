@@ -57,24 +57,20 @@
 //! # Parsing HashMap as strongly typed data structures
 //!
 //! Serde provides a powerful way of mapping HashMap data into Rust data structures
-//! largely automatically by using [serde_dynamodb::from_hashmap][from_hashmap]
-//!
-//! ```rust,ignore
-//! extern crate serde;
-//! extern crate serde_dynamodb;
-//!
-//! extern crate rusoto_core;
-//! extern crate rusoto_dynamodb;
-//!
-//! #[macro_use]
-//! extern crate serde_derive;
+//! largely automatically by using [serde_dynamodb::from_hashmap][from_hashmap],
+//! which a [`Paginator`][paginator] applies to each item returned by a query
 //!
+//! ```rust,no_run
+//! # #[cfg(feature = "rusoto_dynamodb")]
+//! # mod example {
 //! use std::collections::HashMap;
 //!
+//! use futures::TryStreamExt;
 //! use rusoto_core::Region;
-//! use rusoto_dynamodb::{DynamoDb, DynamoDbClient, QueryInput, AttributeValue};
+//! use rusoto_dynamodb::{AttributeValue, DynamoDbClient, QueryError, QueryInput};
+//! use serde::{Deserialize, Serialize};
 //!
-//! use serde_dynamodb::Error;
+//! use serde_dynamodb::{Paginator, RequestError};
 //!
 //! #[derive(Serialize, Deserialize, Debug)]
 //! struct Person {
@@ -83,31 +79,27 @@
 //!     phones: Vec<String>,
 //! }
 //!
-//! fn typed_example() -> Result<(), Error> {
-//!
-//!     let client = DynamoDbClient::simple(Region::UsEast1);
+//! async fn typed_example() -> Result<(), RequestError<QueryError>> {
+//!     let client = DynamoDbClient::new(Region::UsEast1);
 //!
 //!     let mut query = HashMap::new();
 //!     query.insert(String::from(":surname"), AttributeValue {
 //!         s: Some(String::from("Smith")),
 //!         ..Default::default()
 //!     });
-//!     // get data from DynamoDB
-//!     let persons: Vec<Person> = client
-//!         .query(&QueryInput {
+//!     // get data from DynamoDB, following the pages of the query, with an error
+//!     // instead of a panic for the items that can't be deserialized
+//!     let persons: Vec<Person> = Paginator::query(
+//!         &client,
+//!         QueryInput {
 //!             table_name: String::from("person"),
 //!             key_condition_expression: Some(String::from("surname = :surname")),
 //!             expression_attribute_values: Some(query),
 //!             ..Default::default()
-//!         })
-//!         .sync()
-//!         .unwrap()
-//!         .items
-//!         .unwrap_or_else(|| vec![])
-//!         .into_iter()
-//!         .map(|item| serde_dynamodb::from_hashmap(item).unwrap())
-//!         .collect();
-//!
+//!         },
+//!     )
+//!     .try_collect()
+//!     .await?;
 //!
 //!     // Do things just like with any other Rust data structure.
 //!     for p in persons {
@@ -116,8 +108,15 @@
 //!
 //!     Ok(())
 //! }
+//! # }
 //! ```
 //!
+//! See [`Paginator`][paginator] to resume a query or a scan from where it
+//! stopped.
+//!
+//! A [`Table`][table] reads and writes the items of a type deriving `DynamoItem`,
+//! with typed keys and errors.
+//!
 //! # Creating an HashMap by serializing data structures
 //!
 //! A data structure can be converted to an HashMap by
@@ -168,6 +167,44 @@
 //! [`serde_dynamodb::streams::to_hashmap`][to_hashmap_streams]. Those methods work with
 //! [`AttributeValue`][dynamodbstreams_attribute] from [DynamoDB Streams][dynamodbstreams].
 //!
+//! ## json-patch
+//!
+//! Feature `json-patch` is disabled by default and add methods
+//! [`serde_dynamodb::json_patch`][json_patch] and [`serde_dynamodb::to_json_value`][to_json_value]
+//! to convert the changes between two versions of an item to a JSON Patch. Those
+//! methods are also available in the [`streams`][streams] module.
+//!
+//! ## lambda
+//!
+//! Feature `lambda` is disabled by default and add module [`lambda`][lambda] to parse
+//! the event payload of a Lambda function triggered by DynamoDB Streams into typed
+//! [`ChangeEvent`][change_event]s.
+//!
+//! ## kinesis
+//!
+//! Feature `kinesis` is disabled by default and add module [`kinesis`][kinesis] to
+//! decode the records a table writes to Kinesis Data Streams into typed
+//! [`ChangeEvent`][change_event]s.
+//!
+//! ## cursor
+//!
+//! Feature `cursor` is disabled by default and add methods
+//! [`serde_dynamodb::encode_cursor`][encode_cursor] and
+//! [`serde_dynamodb::decode_cursor`][decode_cursor] to hand out a `LastEvaluatedKey`
+//! as a signed, and optionally encrypted, page token that clients can't forge.
+//!
+//! ## chrono and time
+//!
+//! Features `chrono` and `time` are disabled by default and add the types of those
+//! crates to the [`timestamp`][timestamp] encodings: ISO 8601 strings, seconds and
+//! milliseconds since the epoch.
+//!
+//! ## rust_decimal and bigdecimal
+//!
+//! Features `rust_decimal` and `bigdecimal` are disabled by default and add the
+//! decimal types of those crates to the [`number`][number] encoding, to store them as
+//! exact numbers instead of rounding them to a float.
+//!
 //! [aws_doc]: https://docs.aws.amazon.com/amazondynamodb/latest/developerguide/Programming.LowLevelAPI.html#Programming.LowLevelAPI.DataTypeDescriptors
 //! [dynamodb]: https://rusoto.github.io/rusoto/rusoto_dynamodb/index.html
 //! [dynamodb_attribute]: https://rusoto.github.io/rusoto/rusoto_dynamodb/struct.AttributeValue.html
@@ -178,24 +215,93 @@
 //! [streams]: streams/index.html
 //! [to_hashmap_streams]: streams/fn.to_hashmap.html
 //! [from_hashmap_streams]: streams/fn.from_hashmap.html
+//! [paginator]: struct.Paginator.html
+//! [table]: struct.Table.html
+//! [timestamp]: timestamp/index.html
+//! [number]: number/index.html
+//! [encode_cursor]: fn.encode_cursor.html
+//! [decode_cursor]: fn.decode_cursor.html
+//! [json_patch]: fn.json_patch.html
+//! [to_json_value]: fn.to_json_value.html
+//! [lambda]: lambda/index.html
+//! [kinesis]: kinesis/index.html
+//! [change_event]: streams/struct.ChangeEvent.html
 //!
 
 pub mod error;
 
 pub use error::Error;
+#[cfg(feature = "rusoto_dynamodb")]
+pub use error::RequestError;
 
 mod common;
+pub mod number;
+pub mod path;
+pub mod timestamp;
+#[cfg(feature = "rusoto_dynamodb")]
+mod trace;
 
 #[cfg(feature = "rusoto_dynamodb")]
 mod dynamodb;
 #[cfg(feature = "rusoto_dynamodb")]
 pub use dynamodb::de::from_hashmap;
 #[cfg(feature = "rusoto_dynamodb")]
-pub use dynamodb::ser::to_hashmap;
+pub use dynamodb::diff::{diff, diff_items, get_path, Change};
+#[cfg(all(feature = "rusoto_dynamodb", feature = "json-patch"))]
+pub use dynamodb::json_patch::{json_patch, to_json_value, PatchOperation};
+#[cfg(feature = "rusoto_dynamodb")]
+pub use dynamodb::ser::{to_attribute_value, to_hashmap};
 
+#[cfg(feature = "rusoto_dynamodb")]
+pub mod expression;
+#[cfg(feature = "rusoto_dynamodb")]
+mod item;
+#[cfg(feature = "rusoto_dynamodb")]
+pub use item::{from_index_item, BillingMode, DynamoItem, IndexProjection, SecondaryIndex};
+#[cfg(feature = "rusoto_dynamodb")]
+mod batch_get;
+#[cfg(feature = "rusoto_dynamodb")]
+pub use batch_get::{batch_get, batch_get_with_retry_policy, BatchGetOutput};
+#[cfg(feature = "rusoto_dynamodb")]
+mod batch_write;
+#[cfg(feature = "rusoto_dynamodb")]
+pub use batch_write::{BatchWrite, BatchWriteOutput};
+#[cfg(feature = "cursor")]
+mod cursor;
+#[cfg(feature = "cursor")]
+pub use cursor::{decode_cursor, encode_cursor, CursorKey};
+#[cfg(feature = "rusoto_dynamodb")]
+mod paginator;
+#[cfg(feature = "rusoto_dynamodb")]
+pub use paginator::Paginator;
+#[cfg(feature = "rusoto_dynamodb")]
+mod parallel_scan;
+#[cfg(feature = "rusoto_dynamodb")]
+pub use parallel_scan::{ParallelScan, SegmentCheckpoint};
+#[cfg(feature = "rusoto_dynamodb")]
+mod retry;
+#[cfg(feature = "rusoto_dynamodb")]
+pub use retry::RetryPolicy;
+#[cfg(feature = "rusoto_dynamodb")]
+mod schema;
+#[cfg(feature = "rusoto_dynamodb")]
+pub use schema::{create_table_input, update_time_to_live_input};
+#[cfg(feature = "rusoto_dynamodb")]
+mod table;
+#[cfg(feature = "rusoto_dynamodb")]
+pub use table::{Table, TableError};
+#[cfg(feature = "rusoto_dynamodb")]
+mod transaction;
+#[cfg(feature = "rusoto_dynamodb")]
+pub use transaction::{Transaction, TransactionError};
+
+#[cfg(feature = "rusoto_dynamodbstreams")]
+mod change_event;
 #[cfg(feature = "rusoto_dynamodbstreams")]
 mod dynamodbstreams;
 #[cfg(feature = "rusoto_dynamodbstreams")]
+mod processor;
+#[cfg(feature = "rusoto_dynamodbstreams")]
 pub mod streams {
     //! Methods in this module are generated to work with
     //! [`AttributeValue`][dynamodbstreams_attribute] from [DynamoDB Streams][dynamodbstreams]
@@ -205,13 +311,47 @@ pub mod streams {
     //! [dynamodb_attribute]: https://rusoto.github.io/rusoto/rusoto_dynamodb/struct.AttributeValue.html
     //! [dynamodbstreams]: https://rusoto.github.io/rusoto/rusoto_dynamodbstreams/index.html
     //! [dynamodbstreams_attribute]: https://rusoto.github.io/rusoto/rusoto_dynamodbstreams/struct.AttributeValue.html
+    //!
+    //! Stream records can be decoded into typed [`ChangeEvent`][change_event]s, with
+    //! the keys and images of the modified item deserialized.
+    //!
+    //! A batch of records can be handled with a [`BatchProcessor`][batch_processor],
+    //! which reports partial failures in the shape expected by Lambda.
+    //!
+    //! [change_event]: struct.ChangeEvent.html
+    //! [batch_processor]: struct.BatchProcessor.html
+    pub use crate::change_event::{ChangeEvent, EventName, StreamViewType};
     pub use crate::dynamodbstreams::de::from_hashmap;
-    pub use crate::dynamodbstreams::ser::to_hashmap;
+    pub use crate::dynamodbstreams::diff::{diff, diff_items, get_path, Change};
+    #[cfg(feature = "json-patch")]
+    pub use crate::dynamodbstreams::json_patch::{json_patch, to_json_value, PatchOperation};
+    pub use crate::dynamodbstreams::ser::{to_attribute_value, to_hashmap};
+    pub use crate::processor::{BatchItemFailure, BatchProcessor, BatchResponse};
 }
 
+#[cfg(feature = "lambda")]
+pub mod lambda;
+
+#[cfg(feature = "kinesis")]
+pub mod kinesis;
+
 /// A data structure that can be used as a DynamoDB `QueryInput`
+///
+/// `#[derive(ToQueryInput)]` from the `serde_dynamodb_derive` crate implements it
+/// for a `<Name>QueryInput` struct with the fields of `<Name>` as optional filters.
 #[cfg(feature = "rusoto_dynamodb")]
 pub trait ToQueryInput {
     /// Transform this structure as a DynamoDB `QueryInput` on the given `table`
     fn to_query_input(&self, table: String) -> rusoto_dynamodb::QueryInput;
+
+    /// Transform this structure as a DynamoDB `QueryInput` on the given `table`,
+    /// returning an error instead of panicking
+    ///
+    /// # Errors
+    ///
+    /// This fails if the fields set are not enough to build a key condition, or if
+    /// one of their values couldn't be serialized.
+    fn try_to_query_input(&self, table: String) -> error::Result<rusoto_dynamodb::QueryInput> {
+        Ok(self.to_query_input(table))
+    }
 }
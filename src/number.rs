@@ -0,0 +1,230 @@
+//! Exact decimal numbers stored in items.
+//!
+//! Numbers that serde hands over as an `f64` are rounded to the closest float before
+//! they reach DynamoDB, which is not acceptable for money. With the features
+//! `rust_decimal` and `bigdecimal`, a `rust_decimal::Decimal` or a
+//! `bigdecimal::BigDecimal` can be stored as an exact `N`, and read back from one
+//! without going through a float:
+//!
+//! ```rust
+//! # #[cfg(feature = "rust_decimal")]
+//! # {
+//! use std::str::FromStr;
+//!
+//! use rust_decimal::Decimal;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Invoice {
+//!     id: String,
+//!     #[serde(with = "serde_dynamodb::number")]
+//!     total: Decimal,
+//! }
+//!
+//! let invoice = Invoice {
+//!     id: String::from("i1"),
+//!     total: Decimal::from_str("0.30").unwrap(),
+//! };
+//! let item = serde_dynamodb::to_hashmap(&invoice).unwrap();
+//! assert_eq!(item["total"].n.as_deref(), Some("0.30"));
+//! # }
+//! ```
+//!
+//! DynamoDB keeps up to 38 significant digits, for magnitudes between `1E-130` and
+//! `9.99E+125`: numbers that can't be stored are rejected when serializing instead
+//! of being rounded. Use [`option`](option/index.html) for optional numbers.
+
+use serde::de::{Deserialize, Deserializer, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use crate::common::CanonicalNumber;
+
+/// Name of the newtype struct the serializers of this crate write as an `N`.
+pub(crate) const TOKEN: &str = "$serde_dynamodb::Number";
+
+const MAX_DIGITS: usize = 38;
+const MAX_EXPONENT: i64 = 125;
+const MIN_EXPONENT: i64 = -130;
+
+/// A number that can be stored exactly as a DynamoDB `N`.
+pub trait Number: Sized {
+    /// The number, in decimal notation.
+    fn to_number(&self) -> String;
+
+    /// Parse a number as returned by DynamoDB, or `None` if this type can't hold it.
+    fn from_number(number: &str) -> Option<Self>;
+}
+
+#[cfg(feature = "rust_decimal")]
+impl Number for rust_decimal::Decimal {
+    fn to_number(&self) -> String {
+        self.to_string()
+    }
+
+    fn from_number(number: &str) -> Option<Self> {
+        use std::str::FromStr;
+
+        rust_decimal::Decimal::from_str(number)
+            .or_else(|_| rust_decimal::Decimal::from_scientific(number))
+            .ok()
+    }
+}
+
+#[cfg(feature = "bigdecimal")]
+impl Number for bigdecimal::BigDecimal {
+    fn to_number(&self) -> String {
+        self.to_string()
+    }
+
+    fn from_number(number: &str) -> Option<Self> {
+        use std::str::FromStr;
+
+        bigdecimal::BigDecimal::from_str(number).ok()
+    }
+}
+
+/// Check that DynamoDB can store `number` without rounding it.
+fn check(number: &str) -> Result<(), String> {
+    let canonical = match CanonicalNumber::parse(number) {
+        Some(canonical) => canonical,
+        None => return Err(format!("invalid number {}", number)),
+    };
+    if canonical.digits.is_empty() {
+        return Ok(());
+    }
+    if canonical.digits.len() > MAX_DIGITS {
+        return Err(format!(
+            "{} has more than {} significant digits",
+            number, MAX_DIGITS
+        ));
+    }
+    if !(MIN_EXPONENT..=MAX_EXPONENT).contains(&canonical.exponent) {
+        return Err(format!("{} is out of the range of numbers", number));
+    }
+    Ok(())
+}
+
+struct Exact<'a>(&'a str);
+
+impl Serialize for Exact<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_newtype_struct(TOKEN, self.0)
+    }
+}
+
+struct Parsed(String);
+
+impl<'de> Deserialize<'de> for Parsed {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ParsedVisitor;
+
+        impl<'de> Visitor<'de> for ParsedVisitor {
+            type Value = Parsed;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a number")
+            }
+
+            fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Parsed, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                String::deserialize(deserializer).map(Parsed)
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Parsed, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(Parsed(value.to_string()))
+            }
+        }
+
+        deserializer.deserialize_newtype_struct(TOKEN, ParsedVisitor)
+    }
+}
+
+fn to_exact<T: Number, E: serde::ser::Error>(number: &T) -> Result<String, E> {
+    let number = number.to_number();
+    check(&number).map_err(E::custom)?;
+    Ok(number)
+}
+
+fn from_exact<T: Number, E: serde::de::Error>(number: Parsed) -> Result<T, E> {
+    T::from_number(&number.0).ok_or_else(|| E::custom(format!("invalid number {}", number.0)))
+}
+
+/// Serialize `number` as an exact `N`.
+///
+/// # Errors
+///
+/// This fails if DynamoDB can't store the number without rounding it, or if the
+/// serializer fails.
+pub fn serialize<T, S>(number: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Number,
+    S: Serializer,
+{
+    Exact(&to_exact::<_, S::Error>(number)?).serialize(serializer)
+}
+
+/// Deserialize a number from an `N`, without going through a float.
+///
+/// # Errors
+///
+/// This fails if the value isn't a number, or if `T` can't hold it.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: Number,
+    D: Deserializer<'de>,
+{
+    from_exact(Parsed::deserialize(deserializer)?)
+}
+
+/// Serialize an optional number as an exact `N`, to use with
+/// `#[serde(with = "serde_dynamodb::number::option")]`.
+pub mod option {
+    use serde::de::{Deserialize, Deserializer};
+    use serde::ser::Serializer;
+
+    use super::{from_exact, to_exact, Exact, Number, Parsed};
+
+    /// Serialize `number` as an exact `N`, if it is set.
+    ///
+    /// # Errors
+    ///
+    /// This fails if DynamoDB can't store the number without rounding it, or if the
+    /// serializer fails.
+    pub fn serialize<T, S>(number: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Number,
+        S: Serializer,
+    {
+        match number {
+            Some(number) => serializer.serialize_some(&Exact(&to_exact::<_, S::Error>(number)?)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    /// Deserialize an optional number from an `N`, without going through a float.
+    ///
+    /// # Errors
+    ///
+    /// This fails if the value is set and isn't a number, or if `T` can't hold it.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        T: Number,
+        D: Deserializer<'de>,
+    {
+        match Option::<Parsed>::deserialize(deserializer)? {
+            Some(number) => from_exact(number).map(Some),
+            None => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,285 @@
+//! Follow the `LastEvaluatedKey` of `Query` and `Scan` requests across pages.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+use rusoto_core::RusotoError;
+use rusoto_dynamodb::{AttributeValue, DynamoDb, QueryError, QueryInput, ScanError, ScanInput};
+use serde::de::DeserializeOwned;
+
+use crate::dynamodb::de::from_hashmap;
+use crate::error::{RequestError, Result};
+
+type Item = HashMap<String, AttributeValue>;
+
+/// The items of a page, and the key to start the next page from.
+struct Page {
+    items: Vec<Item>,
+    last_evaluated_key: Option<Item>,
+}
+
+type PageFuture<'a, E> =
+    Pin<Box<dyn Future<Output = std::result::Result<Page, RusotoError<E>>> + Send + 'a>>;
+type Fetch<'a, E> = Box<dyn FnMut(Option<Item>, Option<i64>) -> PageFuture<'a, E> + Send + 'a>;
+
+/// A stream of the items returned by a `Query` or a `Scan`, deserialized as `T`.
+///
+/// A new request is sent with the `LastEvaluatedKey` of the previous page as its
+/// `ExclusiveStartKey` until there are no more pages. An item that can't be
+/// deserialized is an error of the stream, which can then keep going with the
+/// next items.
+///
+/// ```rust,no_run
+/// use futures::TryStreamExt;
+/// use rusoto_core::Region;
+/// use rusoto_dynamodb::{DynamoDbClient, QueryInput};
+/// use serde::Deserialize;
+/// use serde_dynamodb::Paginator;
+///
+/// #[derive(Deserialize)]
+/// struct Task {
+///     project: String,
+///     id: String,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct TaskKey {
+///     project: String,
+///     id: String,
+/// }
+///
+/// # async fn run() -> Result<(), serde_dynamodb::RequestError<rusoto_dynamodb::QueryError>> {
+/// let client = DynamoDbClient::new(Region::UsEast1);
+/// let input = QueryInput {
+///     table_name: String::from("tasks"),
+///     ..Default::default()
+/// };
+/// let mut tasks = Paginator::<Task, _>::query(&client, input).max_items(50);
+/// while let Some(task) = tasks.try_next().await? {
+///     println!("{}", task.id);
+/// }
+/// // where to resume from, as the typed key of the last task
+/// let _resume_from: Option<TaskKey> = tasks.last_key()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Paginator<'a, T, E> {
+    fetch: Fetch<'a, E>,
+    page: Option<PageFuture<'a, E>>,
+    items: std::vec::IntoIter<Item>,
+    page_start: Option<Item>,
+    last_evaluated_key: Option<Item>,
+    started: bool,
+    failed: bool,
+    page_size: Option<i64>,
+    remaining: Option<usize>,
+    item: PhantomData<fn() -> T>,
+}
+
+impl<T, E> std::fmt::Debug for Paginator<'_, T, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Paginator")
+            .field("items", &self.items)
+            .field("last_evaluated_key", &self.last_evaluated_key)
+            .field("page_size", &self.page_size)
+            .field("remaining", &self.remaining)
+            .finish()
+    }
+}
+
+impl<'a, T> Paginator<'a, T, QueryError> {
+    /// Page through the items matching `input`.
+    ///
+    /// Its `ExclusiveStartKey` is where the first page starts, and its `Limit` is
+    /// the size of the pages.
+    pub fn query<C>(client: &'a C, input: QueryInput) -> Self
+    where
+        C: DynamoDb + Sync + ?Sized,
+    {
+        let start = input.exclusive_start_key.clone();
+        let page_size = input.limit;
+        Paginator::new(
+            Box::new(move |exclusive_start_key, limit| {
+                let output = client.query(QueryInput {
+                    exclusive_start_key,
+                    limit,
+                    ..input.clone()
+                });
+                Box::pin(async move {
+                    let output = output.await?;
+                    Ok(Page {
+                        items: output.items.unwrap_or_default(),
+                        last_evaluated_key: output.last_evaluated_key,
+                    })
+                })
+            }),
+            start,
+            page_size,
+        )
+    }
+}
+
+impl<'a, T> Paginator<'a, T, ScanError> {
+    /// Page through the items matching `input`.
+    ///
+    /// Its `ExclusiveStartKey` is where the first page starts, and its `Limit` is
+    /// the size of the pages.
+    pub fn scan<C>(client: &'a C, input: ScanInput) -> Self
+    where
+        C: DynamoDb + Sync + ?Sized,
+    {
+        let start = input.exclusive_start_key.clone();
+        let page_size = input.limit;
+        Paginator::new(
+            Box::new(move |exclusive_start_key, limit| {
+                let output = client.scan(ScanInput {
+                    exclusive_start_key,
+                    limit,
+                    ..input.clone()
+                });
+                Box::pin(async move {
+                    let output = output.await?;
+                    Ok(Page {
+                        items: output.items.unwrap_or_default(),
+                        last_evaluated_key: output.last_evaluated_key,
+                    })
+                })
+            }),
+            start,
+            page_size,
+        )
+    }
+}
+
+impl<'a, T, E> Paginator<'a, T, E> {
+    fn new(fetch: Fetch<'a, E>, start: Option<Item>, page_size: Option<i64>) -> Self {
+        Paginator {
+            fetch,
+            page: None,
+            items: Vec::new().into_iter(),
+            page_start: None,
+            last_evaluated_key: start,
+            started: false,
+            failed: false,
+            page_size,
+            remaining: None,
+            item: PhantomData,
+        }
+    }
+
+    /// Read at most `page_size` items per request.
+    pub fn page_size(mut self, page_size: i64) -> Self {
+        self.page_size = Some(page_size);
+        self
+    }
+
+    /// Stop after `max_items` items.
+    ///
+    /// The `Limit` of the last request is lowered so that it ends on the last item,
+    /// and [`last_evaluated_key`](#method.last_evaluated_key) is then where to
+    /// resume from.
+    pub fn max_items(mut self, max_items: usize) -> Self {
+        self.remaining = Some(max_items);
+        self
+    }
+
+    /// The `LastEvaluatedKey` of the last page read, `None` once all pages have been
+    /// read.
+    ///
+    /// Once all the items of that page have been returned by the stream, this is
+    /// the `ExclusiveStartKey` to resume from.
+    pub fn last_evaluated_key(&self) -> Option<&HashMap<String, AttributeValue>> {
+        self.last_evaluated_key.as_ref()
+    }
+
+    /// The `LastEvaluatedKey` of the last page read, deserialized as `K`.
+    ///
+    /// # Errors
+    ///
+    /// This fails if the key can't be deserialized as `K`.
+    pub fn last_key<K: DeserializeOwned>(&self) -> Result<Option<K>> {
+        self.last_evaluated_key
+            .clone()
+            .map(from_hashmap)
+            .transpose()
+    }
+
+    /// The `ExclusiveStartKey` to resume from without missing an item that wasn't
+    /// returned yet, `None` if all items have been read.
+    ///
+    /// While items of a page are left, this is the start of that page, so some
+    /// items may be read again.
+    pub(crate) fn resume_from(&self) -> Option<Option<Item>> {
+        if self.items.len() > 0 {
+            Some(self.page_start.clone())
+        } else if self.started && !self.failed && self.last_evaluated_key.is_none() {
+            None
+        } else {
+            Some(self.last_evaluated_key.clone())
+        }
+    }
+
+    fn has_next_page(&self) -> bool {
+        !self.failed
+            && self.remaining != Some(0)
+            && (!self.started || self.last_evaluated_key.is_some())
+    }
+
+    /// The `Limit` of the next request, capped to the remaining items.
+    fn limit(&self) -> Option<i64> {
+        let remaining = self.remaining.map(|remaining| remaining as i64);
+        match (self.page_size, remaining) {
+            (Some(page_size), Some(remaining)) => Some(page_size.min(remaining)),
+            (page_size, remaining) => page_size.or(remaining),
+        }
+    }
+}
+
+impl<T, E> Stream for Paginator<'_, T, E>
+where
+    T: DeserializeOwned,
+{
+    type Item = std::result::Result<T, RequestError<E>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if this.remaining != Some(0) {
+                if let Some(item) = this.items.next() {
+                    if let Some(remaining) = this.remaining.as_mut() {
+                        *remaining -= 1;
+                    }
+                    return Poll::Ready(Some(from_hashmap(item).map_err(RequestError::Serde)));
+                }
+            }
+            if this.page.is_none() {
+                if !this.has_next_page() {
+                    return Poll::Ready(None);
+                }
+                let limit = this.limit();
+                this.page = Some((this.fetch)(this.last_evaluated_key.clone(), limit));
+            }
+            let page = match this.page.as_mut().map(|page| page.as_mut().poll(cx)) {
+                Some(Poll::Ready(page)) => page,
+                _ => return Poll::Pending,
+            };
+            this.page = None;
+            this.started = true;
+            match page {
+                Ok(page) => {
+                    this.items = page.items.into_iter();
+                    this.page_start =
+                        std::mem::replace(&mut this.last_evaluated_key, page.last_evaluated_key);
+                }
+                Err(error) => {
+                    this.failed = true;
+                    return Poll::Ready(Some(Err(RequestError::Rusoto(error))));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,208 @@
+//! Scan a table in parallel segments, as one stream of items.
+
+use std::collections::{HashMap, VecDeque};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+use rusoto_dynamodb::{AttributeValue, DynamoDb, ScanError, ScanInput};
+use serde::de::DeserializeOwned;
+
+use crate::error::RequestError;
+use crate::paginator::Paginator;
+
+/// How far the scan of a segment went, to resume it later.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SegmentCheckpoint {
+    /// The number of the segment, from 0 to `TotalSegments - 1`.
+    pub segment: i64,
+    /// The `ExclusiveStartKey` to resume the segment from, `None` to start from
+    /// the beginning.
+    pub exclusive_start_key: Option<HashMap<String, AttributeValue>>,
+    /// Whether all the items of the segment have been read.
+    pub done: bool,
+}
+
+/// A stream of the items of a `Scan` split in segments, deserialized as `T`.
+///
+/// Each segment is paged through with a [`Paginator`](struct.Paginator.html), and
+/// items are returned as soon as any segment reads them, so they are not in any
+/// particular order. At most [`max_concurrency`](#method.max_concurrency) segments
+/// are read at the same time, the others waiting for one to be done.
+///
+/// [`checkpoints`](#method.checkpoints) tells where each segment is, so that a scan
+/// that was interrupted can be resumed with [`resume`](#method.resume). A checkpoint
+/// never skips an item that wasn't returned yet, but some items may be returned
+/// again after resuming.
+///
+/// ```rust,no_run
+/// use futures::TryStreamExt;
+/// use rusoto_core::Region;
+/// use rusoto_dynamodb::{DynamoDbClient, ScanInput};
+/// use serde::Deserialize;
+/// use serde_dynamodb::ParallelScan;
+///
+/// #[derive(Deserialize)]
+/// struct Task {
+///     id: String,
+/// }
+///
+/// # async fn run() -> Result<(), serde_dynamodb::RequestError<rusoto_dynamodb::ScanError>> {
+/// let client = DynamoDbClient::new(Region::UsEast1);
+/// let input = ScanInput {
+///     table_name: String::from("tasks"),
+///     ..Default::default()
+/// };
+/// let mut tasks = ParallelScan::<Task>::new(&client, input, 8).max_concurrency(4);
+/// while let Some(task) = tasks.try_next().await? {
+///     println!("{}", task.id);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct ParallelScan<'a, T> {
+    segments: Vec<(i64, Option<Paginator<'a, T, ScanError>>)>,
+    waiting: VecDeque<usize>,
+    running: Vec<usize>,
+    next: usize,
+    max_concurrency: usize,
+}
+
+impl<T> std::fmt::Debug for ParallelScan<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("ParallelScan")
+            .field("checkpoints", &self.checkpoints())
+            .field("max_concurrency", &self.max_concurrency)
+            .finish()
+    }
+}
+
+impl<'a, T> ParallelScan<'a, T> {
+    /// Scan the items matching `input` in `total_segments` segments.
+    pub fn new<C>(client: &'a C, input: ScanInput, total_segments: i64) -> Self
+    where
+        C: DynamoDb + Sync + ?Sized,
+    {
+        let checkpoints = (0..total_segments)
+            .map(|segment| SegmentCheckpoint {
+                segment,
+                exclusive_start_key: None,
+                done: false,
+            })
+            .collect();
+        ParallelScan::resume(client, input, checkpoints)
+    }
+
+    /// Resume a scan of the items matching `input` from the `checkpoints` of all its
+    /// segments.
+    pub fn resume<C>(client: &'a C, input: ScanInput, checkpoints: Vec<SegmentCheckpoint>) -> Self
+    where
+        C: DynamoDb + Sync + ?Sized,
+    {
+        let total_segments = checkpoints.len() as i64;
+        let segments: Vec<_> = checkpoints
+            .into_iter()
+            .map(|checkpoint| {
+                let paginator = if checkpoint.done {
+                    None
+                } else {
+                    Some(Paginator::scan(
+                        client,
+                        ScanInput {
+                            segment: Some(checkpoint.segment),
+                            total_segments: Some(total_segments),
+                            exclusive_start_key: checkpoint.exclusive_start_key,
+                            ..input.clone()
+                        },
+                    ))
+                };
+                (checkpoint.segment, paginator)
+            })
+            .collect();
+        ParallelScan {
+            waiting: (0..segments.len())
+                .filter(|i| segments[*i].1.is_some())
+                .collect(),
+            segments,
+            running: vec![],
+            next: 0,
+            max_concurrency: usize::MAX,
+        }
+    }
+
+    /// Read at most `max_concurrency` segments at the same time.
+    pub fn max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = max_concurrency.max(1);
+        self
+    }
+
+    /// Where each segment is, to resume the scan later.
+    pub fn checkpoints(&self) -> Vec<SegmentCheckpoint> {
+        self.segments
+            .iter()
+            .map(
+                |(segment, paginator)| match paginator.as_ref().and_then(Paginator::resume_from) {
+                    Some(exclusive_start_key) => SegmentCheckpoint {
+                        segment: *segment,
+                        exclusive_start_key,
+                        done: false,
+                    },
+                    None => SegmentCheckpoint {
+                        segment: *segment,
+                        exclusive_start_key: None,
+                        done: true,
+                    },
+                },
+            )
+            .collect()
+    }
+}
+
+impl<T> Stream for ParallelScan<'_, T>
+where
+    T: DeserializeOwned,
+{
+    type Item = Result<T, RequestError<ScanError>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            while this.running.len() < this.max_concurrency {
+                match this.waiting.pop_front() {
+                    Some(segment) => this.running.push(segment),
+                    None => break,
+                }
+            }
+            if this.running.is_empty() {
+                return Poll::Ready(None);
+            }
+
+            let mut finished = None;
+            // start from a different segment each time, so that none is starved
+            for offset in 0..this.running.len() {
+                let position = (this.next + offset) % this.running.len();
+                let paginator = this.segments[this.running[position]]
+                    .1
+                    .as_mut()
+                    .expect("running segments have a paginator");
+                match Pin::new(paginator).poll_next(cx) {
+                    Poll::Ready(Some(item)) => {
+                        this.next = position + 1;
+                        return Poll::Ready(Some(item));
+                    }
+                    Poll::Ready(None) => {
+                        finished = Some(position);
+                        break;
+                    }
+                    Poll::Pending => {}
+                }
+            }
+            match finished {
+                Some(position) => {
+                    this.running.remove(position);
+                }
+                None => return Poll::Pending,
+            }
+        }
+    }
+}
@@ -0,0 +1,72 @@
+//! Paths to attributes nested in an item.
+
+/// An element of an [`AttributePath`](struct.AttributePath.html).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PathElement {
+    /// An attribute of an item or of a map, by name.
+    Attribute(String),
+    /// An element of a list, by index.
+    Index(usize),
+}
+
+/// The path to an attribute, going through maps and lists.
+///
+/// It is displayed the way DynamoDB document paths are written, for example
+/// `address.lines[1]`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct AttributePath {
+    /// The elements of the path, from the item down to the attribute.
+    pub elements: Vec<PathElement>,
+}
+
+impl AttributePath {
+    /// The path to the top level attribute `name`.
+    pub fn attribute(name: &str) -> Self {
+        AttributePath {
+            elements: vec![PathElement::Attribute(name.to_owned())],
+        }
+    }
+
+    /// The path to the attribute `name` of the map at this path.
+    pub fn with_attribute(&self, name: &str) -> Self {
+        let mut elements = self.elements.clone();
+        elements.push(PathElement::Attribute(name.to_owned()));
+        AttributePath { elements }
+    }
+
+    /// The path to the element `index` of the list at this path.
+    pub fn with_index(&self, index: usize) -> Self {
+        let mut elements = self.elements.clone();
+        elements.push(PathElement::Index(index));
+        AttributePath { elements }
+    }
+}
+
+impl From<&str> for AttributePath {
+    /// The path to the top level attribute with this name.
+    fn from(name: &str) -> Self {
+        AttributePath::attribute(name)
+    }
+}
+
+impl From<String> for AttributePath {
+    /// The path to the top level attribute with this name.
+    fn from(name: String) -> Self {
+        AttributePath {
+            elements: vec![PathElement::Attribute(name)],
+        }
+    }
+}
+
+impl std::fmt::Display for AttributePath {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for (i, element) in self.elements.iter().enumerate() {
+            match element {
+                PathElement::Attribute(name) if i == 0 => write!(f, "{}", name)?,
+                PathElement::Attribute(name) => write!(f, ".{}", name)?,
+                PathElement::Index(index) => write!(f, "[{}]", index)?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,195 @@
+//! Process a batch of DynamoDB Streams records, reporting partial failures.
+
+use std::collections::HashSet;
+use std::marker::PhantomData;
+
+use rusoto_dynamodbstreams::Record;
+
+use crate::change_event::ChangeEvent;
+use crate::error::Error;
+
+/// A record that failed to be processed, identified by its sequence number.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BatchItemFailure {
+    /// The sequence number of the record.
+    pub item_identifier: String,
+}
+
+impl serde::Serialize for BatchItemFailure {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("BatchItemFailure", 1)?;
+        state.serialize_field("itemIdentifier", &self.item_identifier)?;
+        state.end()
+    }
+}
+
+/// The response a Lambda function returns to report partial batch failures.
+///
+/// It serializes to the `{"batchItemFailures": [{"itemIdentifier": "..."}]}` shape
+/// expected by Lambda when `ReportBatchItemFailures` is enabled.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BatchResponse {
+    /// The records that failed to be processed, in order.
+    pub batch_item_failures: Vec<BatchItemFailure>,
+}
+
+impl serde::Serialize for BatchResponse {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("BatchResponse", 1)?;
+        state.serialize_field("batchItemFailures", &self.batch_item_failures)?;
+        state.end()
+    }
+}
+
+type DeadLetter<'a> = Box<dyn FnMut(&Record, Error) + 'a>;
+
+/// The value of a partition key, which is a string, a number or bytes.
+type PartitionKey = (Option<String>, Option<String>, Option<bytes::Bytes>);
+
+/// Process a batch of stream records with a typed handler.
+///
+/// Records are handled in order. Once a record fails, the following records with
+/// the same partition key are not handled and are reported as failed too, so that
+/// they are retried in order. Records with other partition keys are still handled,
+/// and so are records without the partition key, which are each on their own.
+///
+/// A failed record is reported by its sequence number. If it has none, it can't be
+/// reported on its own: the whole batch is then reported as failed with an empty
+/// item identifier, which Lambda retries from its start, and the following records
+/// are not handled.
+///
+/// Records that can't be decoded into a `ChangeEvent<K, T>` are reported as
+/// failed, unless a dead-letter callback is set: they are then given to that
+/// callback and considered processed.
+///
+/// ```rust
+/// use serde::Deserialize;
+/// use serde_dynamodb::streams::BatchProcessor;
+///
+/// #[derive(Deserialize)]
+/// struct Key {
+///     id: String,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Task {
+///     id: String,
+///     done: bool,
+/// }
+///
+/// let records = vec![];
+/// let response = BatchProcessor::<Key, Task>::new("id")
+///     .dead_letter(|record, error| eprintln!("can't decode {:?}: {}", record.event_id, error))
+///     .process(records, |event| -> Result<(), String> {
+///         println!("{:?} {}", event.event_name, event.keys.id);
+///         Ok(())
+///     });
+/// assert!(response.batch_item_failures.is_empty());
+/// ```
+pub struct BatchProcessor<'a, K, T> {
+    partition_key: String,
+    dead_letter: Option<DeadLetter<'a>>,
+    phantom: PhantomData<fn() -> (K, T)>,
+}
+
+impl<'a, K, T> std::fmt::Debug for BatchProcessor<'a, K, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("BatchProcessor")
+            .field("partition_key", &self.partition_key)
+            .field("dead_letter", &self.dead_letter.is_some())
+            .finish()
+    }
+}
+
+impl<'a, K, T> BatchProcessor<'a, K, T>
+where
+    K: serde::de::DeserializeOwned,
+    T: serde::de::DeserializeOwned,
+{
+    /// Create a processor for a table whose partition key is the attribute
+    /// `partition_key`, reporting records that can't be decoded as failed.
+    pub fn new(partition_key: &str) -> Self {
+        BatchProcessor {
+            partition_key: String::from(partition_key),
+            dead_letter: None,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Give records that can't be decoded to `callback` instead of reporting them as failed.
+    pub fn dead_letter<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(&Record, Error) + 'a,
+    {
+        self.dead_letter = Some(Box::new(callback));
+        self
+    }
+
+    /// Handle each record of the batch, and return the failures to report to Lambda.
+    pub fn process<F, E>(&mut self, records: Vec<Record>, mut handler: F) -> BatchResponse
+    where
+        F: FnMut(ChangeEvent<K, T>) -> Result<(), E>,
+    {
+        let mut failed_keys: HashSet<PartitionKey> = HashSet::new();
+        let mut batch_item_failures = vec![];
+
+        for record in records {
+            let stream_record = record.dynamodb.as_ref();
+            let sequence_number =
+                stream_record.and_then(|stream_record| stream_record.sequence_number.clone());
+            let key = stream_record
+                .and_then(|stream_record| stream_record.keys.as_ref())
+                .and_then(|keys| keys.get(&self.partition_key))
+                .map(|value| (value.s.clone(), value.n.clone(), value.b.clone()));
+
+            let skipped = matches!(&key, Some(key) if failed_keys.contains(key));
+            if skipped || !self.handle(record, &mut handler) {
+                let item_identifier = match sequence_number {
+                    Some(sequence_number) => sequence_number,
+                    None => {
+                        return BatchResponse {
+                            batch_item_failures: vec![BatchItemFailure::default()],
+                        }
+                    }
+                };
+                if let Some(key) = key {
+                    failed_keys.insert(key);
+                }
+                batch_item_failures.push(BatchItemFailure { item_identifier });
+            }
+        }
+
+        BatchResponse {
+            batch_item_failures,
+        }
+    }
+
+    /// Decode and handle one record, returning whether it was processed.
+    fn handle<F, E>(&mut self, record: Record, handler: &mut F) -> bool
+    where
+        F: FnMut(ChangeEvent<K, T>) -> Result<(), E>,
+    {
+        match self.dead_letter.as_mut() {
+            // decoding consumes the record, which the callback is given on failure
+            Some(dead_letter) => match ChangeEvent::from_record(record.clone()) {
+                Ok(event) => handler(event).is_ok(),
+                Err(error) => {
+                    dead_letter(&record, error);
+                    true
+                }
+            },
+            None => match ChangeEvent::from_record(record) {
+                Ok(event) => handler(event).is_ok(),
+                Err(_) => false,
+            },
+        }
+    }
+}
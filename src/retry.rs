@@ -0,0 +1,62 @@
+//! Retry requests with exponential backoff and jitter.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// How many times, and how often, to retry the parts of a request that DynamoDB
+/// didn't process.
+///
+/// The delay before a retry is picked at random between zero and an exponential
+/// backoff, `base_delay * 2^retry` capped to `max_delay`, so that clients retrying
+/// at the same time spread out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// The number of retries, across all the requests sent for one operation.
+    pub max_retries: u32,
+    /// The delay before the first retry, before jitter.
+    pub base_delay: Duration,
+    /// The maximum delay before a retry.
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 10,
+            base_delay: Duration::from_millis(50),
+            max_delay: Duration::from_secs(10),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Never retry.
+    pub fn none() -> Self {
+        RetryPolicy {
+            max_retries: 0,
+            ..Default::default()
+        }
+    }
+
+    /// The delay before the retry number `retry`, counting from 0.
+    pub fn delay(&self, retry: u32) -> Duration {
+        let backoff = self
+            .base_delay
+            .checked_mul(2u32.saturating_pow(retry))
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay);
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u32(retry);
+        let jitter = hasher.finish() as f64 / u64::MAX as f64;
+        backoff.mul_f64(jitter)
+    }
+
+    /// Wait before the retry number `retry`.
+    pub(crate) async fn wait(&self, retry: u32) {
+        let delay = self.delay(retry);
+        if delay > Duration::from_secs(0) {
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
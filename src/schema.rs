@@ -0,0 +1,160 @@
+//! Describe the table of a `DynamoItem`.
+
+use rusoto_dynamodb::{
+    AttributeDefinition, CreateTableInput, GlobalSecondaryIndex, KeySchemaElement,
+    LocalSecondaryIndex, Projection, ProvisionedThroughput, StreamSpecification,
+    TimeToLiveSpecification, UpdateTimeToLiveInput,
+};
+use serde::de::DeserializeOwned;
+
+use crate::error::{Error, Result};
+use crate::item::{BillingMode, DynamoItem, IndexProjection};
+use crate::path::AttributePath;
+use crate::trace::trace;
+
+fn key_schema(partition_key: &str, sort_key: Option<&str>) -> Vec<KeySchemaElement> {
+    std::iter::once((partition_key, "HASH"))
+        .chain(sort_key.map(|sort_key| (sort_key, "RANGE")))
+        .map(|(attribute_name, key_type)| KeySchemaElement {
+            attribute_name: attribute_name.to_owned(),
+            key_type: key_type.to_owned(),
+        })
+        .collect()
+}
+
+fn projection(projection: IndexProjection) -> Projection {
+    match projection {
+        IndexProjection::All => Projection {
+            projection_type: Some(String::from("ALL")),
+            non_key_attributes: None,
+        },
+        IndexProjection::KeysOnly => Projection {
+            projection_type: Some(String::from("KEYS_ONLY")),
+            non_key_attributes: None,
+        },
+        IndexProjection::Include(attributes) => Projection {
+            projection_type: Some(String::from("INCLUDE")),
+            non_key_attributes: Some(attributes.iter().map(|name| (*name).to_owned()).collect()),
+        },
+    }
+}
+
+/// The `CreateTableInput` of the table of `T`.
+///
+/// The key schemas of the table and of its secondary indexes come from
+/// [`DynamoItem`](trait.DynamoItem.html), and the types of the key attributes are
+/// found by tracing the `Deserialize` implementation of `T`: strings are `S`,
+/// numbers are `N` and bytes are `B`.
+///
+/// `Deserialize` is traced rather than `Serialize` as no item is needed to do it:
+/// a struct deserializes from the names of its fields, while it can only be
+/// serialized from a value. The types are then those read back from an item,
+/// which are the ones written for the derived implementations and
+/// `#[serde(with = "...")]`. A key field with a `deserialize_with` function that
+/// reads another type than its `serialize_with` function writes, or that reads
+/// any type, such as an untagged enum, gets a wrong type or none.
+///
+/// # Errors
+///
+/// This fails if a key attribute isn't a field of `T`, or isn't a string, a number
+/// or bytes.
+pub fn create_table_input<T>() -> Result<CreateTableInput>
+where
+    T: DynamoItem + DeserializeOwned,
+{
+    let (trace, _) = trace::<T>();
+
+    let key_attributes = std::iter::once(T::PARTITION_KEY).chain(T::SORT_KEY).chain(
+        T::INDEXES
+            .iter()
+            .flat_map(|index| std::iter::once(index.partition_key).chain(index.sort_key)),
+    );
+    let mut attribute_definitions: Vec<AttributeDefinition> = vec![];
+    for name in key_attributes {
+        if attribute_definitions
+            .iter()
+            .any(|definition| definition.attribute_name == name)
+        {
+            continue;
+        }
+        let attribute_type = trace
+            .scalar_type(&AttributePath::attribute(name))
+            .ok_or_else(|| Error {
+                message: format!(
+                    "key attribute {} of table {} is not a string, a number or bytes",
+                    name,
+                    T::TABLE_NAME
+                ),
+            })?;
+        attribute_definitions.push(AttributeDefinition {
+            attribute_name: name.to_owned(),
+            attribute_type: attribute_type.as_str().to_owned(),
+        });
+    }
+
+    let (billing_mode, provisioned_throughput) = match T::BILLING_MODE {
+        BillingMode::PayPerRequest => ("PAY_PER_REQUEST", None),
+        BillingMode::Provisioned {
+            read_capacity_units,
+            write_capacity_units,
+        } => (
+            "PROVISIONED",
+            Some(ProvisionedThroughput {
+                read_capacity_units,
+                write_capacity_units,
+            }),
+        ),
+    };
+
+    let global_secondary_indexes: Vec<_> = T::INDEXES
+        .iter()
+        .filter(|index| !index.local)
+        .map(|index| GlobalSecondaryIndex {
+            index_name: index.name.to_owned(),
+            key_schema: key_schema(index.partition_key, index.sort_key),
+            projection: projection(index.projection),
+            provisioned_throughput: provisioned_throughput.clone(),
+        })
+        .collect();
+    let local_secondary_indexes: Vec<_> = T::INDEXES
+        .iter()
+        .filter(|index| index.local)
+        .map(|index| LocalSecondaryIndex {
+            index_name: index.name.to_owned(),
+            key_schema: key_schema(index.partition_key, index.sort_key),
+            projection: projection(index.projection),
+        })
+        .collect();
+
+    Ok(CreateTableInput {
+        table_name: T::TABLE_NAME.to_owned(),
+        attribute_definitions,
+        key_schema: key_schema(T::PARTITION_KEY, T::SORT_KEY),
+        global_secondary_indexes: Some(global_secondary_indexes)
+            .filter(|indexes| !indexes.is_empty()),
+        local_secondary_indexes: Some(local_secondary_indexes)
+            .filter(|indexes| !indexes.is_empty()),
+        billing_mode: Some(billing_mode.to_owned()),
+        provisioned_throughput,
+        stream_specification: T::STREAM_VIEW_TYPE.map(|stream_view_type| StreamSpecification {
+            stream_enabled: true,
+            stream_view_type: Some(stream_view_type.to_owned()),
+        }),
+        ..Default::default()
+    })
+}
+
+/// The `UpdateTimeToLiveInput` enabling time to live on the table of `T`, if it
+/// has a time to live attribute.
+///
+/// Time to live can't be set when creating a table, so this has to be sent once
+/// the table is active.
+pub fn update_time_to_live_input<T: DynamoItem>() -> Option<UpdateTimeToLiveInput> {
+    T::TTL_ATTRIBUTE.map(|attribute_name| UpdateTimeToLiveInput {
+        table_name: T::TABLE_NAME.to_owned(),
+        time_to_live_specification: TimeToLiveSpecification {
+            attribute_name: attribute_name.to_owned(),
+            enabled: true,
+        },
+    })
+}
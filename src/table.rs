@@ -0,0 +1,513 @@
+//! A typed repository over the items of a table.
+
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use rusoto_core::RusotoError;
+use rusoto_dynamodb::{
+    AttributeValue, DeleteItemError, DeleteItemInput, DynamoDb, GetItemError, GetItemInput,
+    PutItemError, PutItemInput, QueryError, ScanError, ScanInput, UpdateItemError, UpdateItemInput,
+};
+use serde::de::DeserializeOwned;
+
+use crate::dynamodb::de::from_hashmap;
+use crate::dynamodb::diff::diff_items;
+use crate::dynamodb::ser::to_hashmap;
+use crate::error::{Error, RequestError, Result};
+use crate::expression::{query_input, Condition, ExpressionAttributes, Update};
+use crate::item::DynamoItem;
+use crate::paginator::Paginator;
+
+type Item = HashMap<String, AttributeValue>;
+
+/// An error of an operation of a [`Table`](struct.Table.html).
+#[derive(Debug)]
+pub enum TableError<E> {
+    /// There is no item with this key.
+    NotFound,
+    /// The condition of the write wasn't met, as an item with the same key already
+    /// existing for [`put_if_absent`](struct.Table.html#method.put_if_absent).
+    ConditionalCheckFailed,
+    /// The item was written by someone else since it was read: its version isn't
+    /// the expected one anymore.
+    ConcurrentModification,
+    /// An item couldn't be serialized or deserialized, or the request failed.
+    Request(RequestError<E>),
+}
+impl<E> From<Error> for TableError<E> {
+    fn from(error: Error) -> Self {
+        TableError::Request(RequestError::Serde(error))
+    }
+}
+impl<E> From<RusotoError<E>> for TableError<E> {
+    fn from(error: RusotoError<E>) -> Self {
+        TableError::Request(RequestError::Rusoto(error))
+    }
+}
+impl<E: std::error::Error + 'static> std::fmt::Display for TableError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TableError::NotFound => write!(f, "item not found"),
+            TableError::ConditionalCheckFailed => write!(f, "conditional check failed"),
+            TableError::ConcurrentModification => write!(f, "item modified concurrently"),
+            TableError::Request(error) => std::fmt::Display::fmt(error, f),
+        }
+    }
+}
+impl<E: std::error::Error + 'static> std::error::Error for TableError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TableError::Request(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// The version of `item` in its attribute `name`, `None` if it was never written.
+fn version(item: &Item, name: &str) -> Result<Option<u64>> {
+    let value = match item.get(name) {
+        Some(value) if value.null != Some(true) => value,
+        _ => return Ok(None),
+    };
+    match value.n.as_deref().map(str::parse) {
+        Some(Ok(0)) => Ok(None),
+        Some(Ok(version)) => Ok(Some(version)),
+        _ => Err(Error {
+            message: format!("the version {} isn't a positive integer", name),
+        }),
+    }
+}
+
+fn number(value: u64) -> AttributeValue {
+    AttributeValue {
+        n: Some(value.to_string()),
+        ..Default::default()
+    }
+}
+
+/// Add `action` to the `SET` clause of the update expression `expression`.
+fn set_first(expression: Option<String>, action: String) -> Option<String> {
+    Some(match expression {
+        Some(expression) if expression.starts_with("SET ") => {
+            format!("SET {}, {}", action, &expression[4..])
+        }
+        Some(expression) => format!("SET {} {}", action, expression),
+        None => format!("SET {}", action),
+    })
+}
+
+/// Whether `value` is missing or null.
+fn is_unset(value: Option<&AttributeValue>) -> bool {
+    match value {
+        Some(value) => value.null == Some(true),
+        None => true,
+    }
+}
+
+/// Check that the time to live of `item`, if it has one, is a number, as DynamoDB
+/// ignores it otherwise.
+fn check_ttl<T: DynamoItem>(item: &Item) -> Result<()> {
+    match T::TTL_ATTRIBUTE.and_then(|name| Some((name, item.get(name)?))) {
+        Some((name, value)) if value.n.is_none() && !is_unset(Some(value)) => Err(Error {
+            message: format!(
+                "the time to live {} must be a number of seconds since the epoch",
+                name
+            ),
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Set the creation and update times of `item`, for a put.
+fn set_timestamps<T: DynamoItem>(item: &mut Item) -> Result<()> {
+    if let Some(name) = T::CREATED_AT_ATTRIBUTE {
+        if is_unset(item.get(name)) {
+            item.insert(name.to_owned(), T::timestamp(name)?);
+        }
+    }
+    if let Some(name) = T::UPDATED_AT_ATTRIBUTE {
+        item.insert(name.to_owned(), T::timestamp(name)?);
+    }
+    Ok(())
+}
+
+/// Increment the version of `item` in its attribute `name`, and return the
+/// condition that the item in the table still has the previous version, and
+/// whether it was written before.
+fn increment_version(item: &mut Item, name: &str) -> Result<(Condition, bool)> {
+    let expected = version(item, name)?;
+    item.insert(name.to_owned(), number(expected.unwrap_or(0) + 1));
+    Ok(match expected {
+        Some(expected) => (Condition::eq(name, &expected), true),
+        None => (Condition::attribute_not_exists(name), false),
+    })
+}
+
+/// The items of the table of `T`, read and written through `client`.
+///
+/// Items are serialized with [`to_hashmap`](fn.to_hashmap.html) and deserialized with
+/// [`from_hashmap`](fn.from_hashmap.html), and keys are the `Key` structs generated
+/// by `#[derive(DynamoItem)]`.
+///
+/// If `T` has a [`VERSION_ATTRIBUTE`](trait.DynamoItem.html#associatedconstant.VERSION_ATTRIBUTE),
+/// puts and [`update_changes`](#method.update_changes) only succeed if the item in
+/// the table still has the version of the item being written, and increment it.
+/// Otherwise they fail with `ConcurrentModification`, and the item should be read
+/// again before retrying. [`update`](#method.update) can't check the version, so it
+/// isn't available for such items.
+///
+/// The [`UPDATED_AT_ATTRIBUTE`](trait.DynamoItem.html#associatedconstant.UPDATED_AT_ATTRIBUTE)
+/// is set to the current time on each put and update. The
+/// [`CREATED_AT_ATTRIBUTE`](trait.DynamoItem.html#associatedconstant.CREATED_AT_ATTRIBUTE)
+/// is set if the item doesn't have one: by a put if it is missing from the item
+/// being written, by an update if it is missing from the item in the table. A put
+/// can only leave it unset if it is an `Option`, so `#[derive(DynamoItem)]` rejects
+/// other types:
+///
+/// ```rust,compile_fail
+/// use std::time::SystemTime;
+///
+/// use serde::{Deserialize, Serialize};
+/// use serde_dynamodb_derive::DynamoItem;
+///
+/// #[derive(Serialize, Deserialize, DynamoItem)]
+/// #[dynamodb(table = "sessions")]
+/// struct Session {
+///     #[dynamodb(partition_key)]
+///     id: String,
+///     #[dynamodb(created_at)]
+///     #[serde(with = "serde_dynamodb::timestamp::epoch_seconds")]
+///     created: SystemTime,
+/// }
+/// ```
+///
+/// Puts and [`update_changes`](#method.update_changes) reject an item whose time to live
+/// isn't a number, as DynamoDB would never expire it.
+///
+/// ```rust,no_run
+/// use rusoto_core::Region;
+/// use rusoto_dynamodb::DynamoDbClient;
+/// use serde::{Deserialize, Serialize};
+/// use serde_dynamodb::{Table, TableError};
+/// use serde_dynamodb_derive::DynamoItem;
+///
+/// #[derive(Serialize, Deserialize, DynamoItem)]
+/// #[dynamodb(table = "tasks")]
+/// struct Task {
+///     #[dynamodb(partition_key)]
+///     id: String,
+///     title: String,
+/// }
+///
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = DynamoDbClient::new(Region::UsEast1);
+/// let tasks = Table::<Task, _>::new(&client);
+/// let key = TaskKey { id: String::from("1") };
+/// match tasks.get(&key).await {
+///     Ok(task) => println!("{}", task.title),
+///     Err(TableError::NotFound) => println!("no task"),
+///     Err(error) => return Err(error.into()),
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct Table<'a, T, C: ?Sized> {
+    client: &'a C,
+    table_name: String,
+    item: PhantomData<fn() -> T>,
+}
+
+impl<T, C: ?Sized> std::fmt::Debug for Table<'_, T, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Table")
+            .field("table_name", &self.table_name)
+            .finish()
+    }
+}
+
+impl<'a, T, C> Table<'a, T, C>
+where
+    T: DynamoItem + serde::Serialize + DeserializeOwned,
+    C: DynamoDb + Sync + ?Sized,
+{
+    /// The table of `T`.
+    pub fn new(client: &'a C) -> Self {
+        Table::with_table_name(client, T::TABLE_NAME.to_owned())
+    }
+
+    /// The table `table_name`, with the same key as the table of `T`, as a table
+    /// whose name depends on the environment.
+    pub fn with_table_name(client: &'a C, table_name: String) -> Self {
+        Table {
+            client,
+            table_name,
+            item: PhantomData,
+        }
+    }
+
+    /// The name of the table.
+    pub fn table_name(&self) -> &str {
+        &self.table_name
+    }
+
+    /// Read the item with the key `key`.
+    ///
+    /// # Errors
+    ///
+    /// This fails with `NotFound` if there is no item with this key.
+    pub async fn get(&self, key: &T::Key) -> std::result::Result<T, TableError<GetItemError>> {
+        let output = self
+            .client
+            .get_item(GetItemInput {
+                table_name: self.table_name.clone(),
+                key: to_hashmap(key)?,
+                ..Default::default()
+            })
+            .await?;
+        match output.item {
+            Some(item) => Ok(from_hashmap(item)?),
+            None => Err(TableError::NotFound),
+        }
+    }
+
+    /// Write `item`, replacing the item with the same key if there is one, and
+    /// return it as written, with its new version.
+    ///
+    /// # Errors
+    ///
+    /// This fails with `ConcurrentModification` if the version of the item in the
+    /// table isn't the version of `item`.
+    pub async fn put(&self, item: &T) -> std::result::Result<T, TableError<PutItemError>> {
+        self.put_with_condition(item, None).await
+    }
+
+    /// Write `item` only if there is no item with the same key, and return it as
+    /// written, with its new version.
+    ///
+    /// # Errors
+    ///
+    /// This fails with `ConditionalCheckFailed` if there already is an item with
+    /// this key.
+    pub async fn put_if_absent(
+        &self,
+        item: &T,
+    ) -> std::result::Result<T, TableError<PutItemError>> {
+        self.put_with_condition(
+            item,
+            Some(Condition::attribute_not_exists(T::PARTITION_KEY)),
+        )
+        .await
+    }
+
+    async fn put_with_condition(
+        &self,
+        item: &T,
+        condition: Option<Condition>,
+    ) -> std::result::Result<T, TableError<PutItemError>> {
+        let mut item = to_hashmap(item)?;
+        check_ttl::<T>(&item)?;
+        set_timestamps::<T>(&mut item)?;
+        let mut failure = TableError::ConditionalCheckFailed;
+        let version = match T::VERSION_ATTRIBUTE {
+            Some(name) => {
+                if condition.is_none() {
+                    failure = TableError::ConcurrentModification;
+                }
+                Some(increment_version(&mut item, name)?.0)
+            }
+            None => None,
+        };
+        let mut input = PutItemInput {
+            table_name: self.table_name.clone(),
+            item: item.clone(),
+            ..Default::default()
+        };
+        if let Some(condition) = Condition::all(condition.into_iter().chain(version)) {
+            let mut attributes = ExpressionAttributes::new();
+            input.condition_expression = Some(condition.build(&mut attributes)?);
+            input.expression_attribute_names = attributes.names();
+            input.expression_attribute_values = attributes.values();
+        }
+        match self.client.put_item(input).await {
+            Ok(_) => Ok(from_hashmap(item)?),
+            Err(RusotoError::Service(PutItemError::ConditionalCheckFailed(_))) => Err(failure),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// Delete the item with the key `key`, and return it.
+    ///
+    /// # Errors
+    ///
+    /// This fails with `NotFound` if there is no item with this key.
+    pub async fn delete(
+        &self,
+        key: &T::Key,
+    ) -> std::result::Result<T, TableError<DeleteItemError>> {
+        let output = self
+            .client
+            .delete_item(DeleteItemInput {
+                table_name: self.table_name.clone(),
+                key: to_hashmap(key)?,
+                return_values: Some(String::from("ALL_OLD")),
+                ..Default::default()
+            })
+            .await?;
+        match output.attributes {
+            Some(item) => Ok(from_hashmap(item)?),
+            None => Err(TableError::NotFound),
+        }
+    }
+
+    /// Apply `update` to the item with the key `key`, and return the updated item.
+    ///
+    /// Unlike `UpdateItem`, this never creates an item. If `T` has a version, the
+    /// version `update` was made from isn't known, so the update couldn't be
+    /// checked: use [`update_changes`](#method.update_changes) instead.
+    ///
+    /// The creation and update times of `T` are set, unless `update` already has
+    /// an action on them, which is then kept as it is.
+    ///
+    /// # Errors
+    ///
+    /// This fails with `NotFound` if there is no item with this key, and before
+    /// sending any request if `T` has a version.
+    pub async fn update(
+        &self,
+        key: &T::Key,
+        update: Update,
+    ) -> std::result::Result<T, TableError<UpdateItemError>> {
+        if let Some(name) = T::VERSION_ATTRIBUTE {
+            return Err(Error {
+                message: format!(
+                    "the version {} can't be checked by update, use update_changes",
+                    name
+                ),
+            }
+            .into());
+        }
+        self.update_with_condition(to_hashmap(key)?, update, None)
+            .await
+    }
+
+    /// Write the changes from `before` to `after`, and return the updated item.
+    ///
+    /// Unlike `UpdateItem`, this never creates an item. If `T` has a version, the
+    /// item is only updated if it still has the version of `before`.
+    ///
+    /// # Errors
+    ///
+    /// This fails with `NotFound` if there is no item with the key of `before`, or
+    /// with `ConcurrentModification` if its version isn't the version of `before`.
+    pub async fn update_changes(
+        &self,
+        before: &T,
+        after: &T,
+    ) -> std::result::Result<T, TableError<UpdateItemError>> {
+        let key_attributes: Vec<&str> = std::iter::once(T::PARTITION_KEY)
+            .chain(T::SORT_KEY)
+            .collect();
+        let before = to_hashmap(before)?;
+        let mut after = to_hashmap(after)?;
+        let mut version = None;
+        if let Some(name) = T::VERSION_ATTRIBUTE {
+            let mut next = before.clone();
+            version = Some(increment_version(&mut next, name)?);
+            after.insert(name.to_owned(), next[name].clone());
+        }
+        // the timestamps are set by the update itself
+        for name in T::CREATED_AT_ATTRIBUTE
+            .iter()
+            .chain(T::UPDATED_AT_ATTRIBUTE.iter())
+        {
+            match before.get(*name) {
+                Some(value) => after.insert((*name).to_owned(), value.clone()),
+                None => after.remove(*name),
+            };
+        }
+        check_ttl::<T>(&after)?;
+        let update = Update::from_changes(&diff_items(&before, &after), &after, &key_attributes);
+        let key = key_attributes
+            .iter()
+            .filter_map(|name| Some(((*name).to_owned(), before.get(*name)?.clone())))
+            .collect();
+        self.update_with_condition(key, update, version).await
+    }
+
+    async fn update_with_condition(
+        &self,
+        key: Item,
+        update: Update,
+        version: Option<(Condition, bool)>,
+    ) -> std::result::Result<T, TableError<UpdateItemError>> {
+        // an action of the caller on a timestamp replaces the one added here
+        let created_at = T::CREATED_AT_ATTRIBUTE.filter(|name| !update.writes(name));
+        let updated_at = T::UPDATED_AT_ATTRIBUTE.filter(|name| !update.writes(name));
+        let mut attributes = update.attributes;
+        let mut update_expression = update.update_expression;
+        if let Some(name) = created_at {
+            let value = T::timestamp(name)?;
+            let name = attributes.name(name);
+            let action = format!(
+                "{} = if_not_exists({}, {})",
+                name,
+                name,
+                attributes.value(value)
+            );
+            update_expression = set_first(update_expression, action);
+        }
+        if let Some(name) = updated_at {
+            let value = T::timestamp(name)?;
+            let action = format!("{} = {}", attributes.name(name), attributes.value(value));
+            update_expression = set_first(update_expression, action);
+        }
+        let mut failure = TableError::NotFound;
+        let mut condition = Condition::attribute_exists(T::PARTITION_KEY);
+        if let Some((version, written)) = version {
+            condition = condition.and(version);
+            if written {
+                failure = TableError::ConcurrentModification;
+            }
+        }
+        let input = UpdateItemInput {
+            table_name: self.table_name.clone(),
+            key,
+            update_expression,
+            condition_expression: Some(condition.build(&mut attributes)?),
+            expression_attribute_names: attributes.names(),
+            expression_attribute_values: attributes.values(),
+            return_values: Some(String::from("ALL_NEW")),
+            ..Default::default()
+        };
+        match self.client.update_item(input).await {
+            Ok(output) => Ok(from_hashmap(output.attributes.unwrap_or_default())?),
+            Err(RusotoError::Service(UpdateItemError::ConditionalCheckFailed(_))) => Err(failure),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// The items matching `key_condition`, and `filter` if there is one.
+    ///
+    /// # Errors
+    ///
+    /// This fails if a value of the conditions couldn't be serialized.
+    pub fn query(
+        &self,
+        key_condition: Condition,
+        filter: Option<Condition>,
+    ) -> Result<Paginator<'a, T, QueryError>> {
+        let input = query_input(self.table_name.clone(), key_condition, filter)?;
+        Ok(Paginator::query(self.client, input))
+    }
+
+    /// All the items of the table.
+    pub fn scan(&self) -> Paginator<'a, T, ScanError> {
+        Paginator::scan(
+            self.client,
+            ScanInput {
+                table_name: self.table_name.clone(),
+                ..Default::default()
+            },
+        )
+    }
+}
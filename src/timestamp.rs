@@ -0,0 +1,573 @@
+//! Times stored in items.
+//!
+//! DynamoDB only deletes expired items if their time to live attribute is a number
+//! of seconds since the epoch, while serde serializes a `SystemTime` as a map and a
+//! `Duration` as a map of its seconds and nanoseconds. [`epoch_seconds`](epoch_seconds/index.html)
+//! serializes them as a number instead:
+//!
+//! ```rust
+//! # #[cfg(feature = "rusoto_dynamodb")]
+//! # {
+//! use std::time::{Duration, SystemTime};
+//!
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Session {
+//!     id: String,
+//!     #[serde(with = "serde_dynamodb::timestamp::epoch_seconds")]
+//!     expires: SystemTime,
+//! }
+//!
+//! let session = Session {
+//!     id: String::from("s1"),
+//!     expires: SystemTime::UNIX_EPOCH + Duration::from_secs(1_600_000_000),
+//! };
+//! let item = serde_dynamodb::to_hashmap(&session).unwrap();
+//! assert_eq!(item["expires"].n.as_deref(), Some("1600000000"));
+//! # }
+//! ```
+//!
+//! With the features `chrono` and `time`, the `DateTime<Utc>` and `NaiveDate` of
+//! [chrono](https://docs.rs/chrono) and the `OffsetDateTime` of
+//! [time](https://docs.rs/time) can also be stored as:
+//!
+//! * [`iso8601`](iso8601/index.html): a string, always in UTC and with milliseconds,
+//!   such as `2020-09-13T12:26:40.000Z`, or `2020-09-13` for a date. As they all have
+//!   the same length, they sort in the same order as the times they represent,
+//!   which makes them usable as sort keys.
+//! * [`epoch_seconds`](epoch_seconds/index.html): a number of seconds since the epoch.
+//! * [`epoch_millis`](epoch_millis/index.html): a number of milliseconds since the
+//!   epoch.
+//!
+//! Each encoding has an `option` module for optional times, such as
+//! `#[serde(with = "serde_dynamodb::timestamp::iso8601::option")]`.
+
+use std::convert::TryFrom;
+use std::time::{Duration, SystemTime};
+
+/// A type of time that can be set to the current time, for the creation and update
+/// times of items.
+pub trait Timestamp {
+    /// The current time.
+    fn now() -> Self;
+}
+
+impl Timestamp for SystemTime {
+    fn now() -> Self {
+        SystemTime::now()
+    }
+}
+
+impl<T: Timestamp> Timestamp for Option<T> {
+    fn now() -> Self {
+        Some(T::now())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Timestamp for chrono::DateTime<chrono::Utc> {
+    fn now() -> Self {
+        chrono::Utc::now()
+    }
+}
+
+/// Today, in UTC.
+#[cfg(feature = "chrono")]
+impl Timestamp for chrono::NaiveDate {
+    fn now() -> Self {
+        chrono::Utc::now().date_naive()
+    }
+}
+
+#[cfg(feature = "time")]
+impl Timestamp for time::OffsetDateTime {
+    fn now() -> Self {
+        time::OffsetDateTime::now_utc()
+    }
+}
+
+/// A time that can be converted from and to a number of seconds since the epoch.
+pub trait EpochSeconds: Sized {
+    /// The number of seconds since the epoch, negative before it and rounded down,
+    /// `None` if it can't be represented.
+    fn to_epoch_seconds(&self) -> Option<i64>;
+    /// The time `seconds` seconds after the epoch, `None` if it can't be
+    /// represented.
+    fn from_epoch_seconds(seconds: i64) -> Option<Self>;
+}
+
+/// A time that can be converted from and to a number of milliseconds since the
+/// epoch.
+pub trait EpochMillis: Sized {
+    /// The number of milliseconds since the epoch, negative before it and rounded
+    /// down, `None` if it can't be represented.
+    fn to_epoch_millis(&self) -> Option<i64>;
+    /// The time `millis` milliseconds after the epoch, `None` if it can't be
+    /// represented.
+    fn from_epoch_millis(millis: i64) -> Option<Self>;
+}
+
+/// A time that can be converted from and to an ISO 8601 string.
+pub trait Iso8601: Sized {
+    /// The ISO 8601 string, in UTC and with a fixed precision.
+    fn to_iso8601(&self) -> String;
+    /// The time of an ISO 8601 string, in any time zone and with any precision,
+    /// `None` if it isn't valid.
+    fn from_iso8601(iso8601: &str) -> Option<Self>;
+}
+
+/// The time since the epoch, negative before it and rounded down, in `unit` of a
+/// second.
+fn since_epoch(time: SystemTime, unit: i128) -> Option<i64> {
+    let nanos = match time.duration_since(SystemTime::UNIX_EPOCH) {
+        Ok(after) => i128::try_from(after.as_nanos()).ok()?,
+        Err(before) => -i128::try_from(before.duration().as_nanos()).ok()?,
+    };
+    i64::try_from(nanos.div_euclid(unit)).ok()
+}
+
+/// The time `count` `unit` of a second after the epoch.
+fn after_epoch(count: i64, unit: u64) -> Option<SystemTime> {
+    let per_second = SECOND / unit;
+    let count_abs = count.unsigned_abs();
+    let duration = Duration::new(
+        count_abs / per_second,
+        ((count_abs % per_second) * unit) as u32,
+    );
+    if count >= 0 {
+        SystemTime::UNIX_EPOCH.checked_add(duration)
+    } else {
+        SystemTime::UNIX_EPOCH.checked_sub(duration)
+    }
+}
+
+const SECOND: u64 = 1_000_000_000;
+const MILLISECOND: u64 = 1_000_000;
+
+impl EpochSeconds for SystemTime {
+    fn to_epoch_seconds(&self) -> Option<i64> {
+        since_epoch(*self, SECOND.into())
+    }
+
+    fn from_epoch_seconds(seconds: i64) -> Option<Self> {
+        after_epoch(seconds, SECOND)
+    }
+}
+
+impl EpochMillis for SystemTime {
+    fn to_epoch_millis(&self) -> Option<i64> {
+        since_epoch(*self, MILLISECOND.into())
+    }
+
+    fn from_epoch_millis(millis: i64) -> Option<Self> {
+        after_epoch(millis, MILLISECOND)
+    }
+}
+
+/// A `Duration` is a time relative to now: it is serialized as the time when it
+/// elapses, and deserialized as the time left until then, zero if it is past.
+impl EpochSeconds for Duration {
+    fn to_epoch_seconds(&self) -> Option<i64> {
+        SystemTime::now().checked_add(*self)?.to_epoch_seconds()
+    }
+
+    fn from_epoch_seconds(seconds: i64) -> Option<Self> {
+        SystemTime::from_epoch_seconds(seconds)
+            .map(|time| time.duration_since(SystemTime::now()).unwrap_or_default())
+    }
+}
+
+/// A `Duration` is a time relative to now: it is serialized as the time when it
+/// elapses, and deserialized as the time left until then, zero if it is past.
+impl EpochMillis for Duration {
+    fn to_epoch_millis(&self) -> Option<i64> {
+        SystemTime::now().checked_add(*self)?.to_epoch_millis()
+    }
+
+    fn from_epoch_millis(millis: i64) -> Option<Self> {
+        SystemTime::from_epoch_millis(millis)
+            .map(|time| time.duration_since(SystemTime::now()).unwrap_or_default())
+    }
+}
+
+#[cfg(feature = "chrono")]
+mod chrono_impls {
+    use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+
+    use super::{EpochMillis, EpochSeconds, Iso8601};
+
+    fn midnight(date: &NaiveDate) -> DateTime<Utc> {
+        date.and_time(chrono::NaiveTime::MIN).and_utc()
+    }
+
+    impl EpochSeconds for DateTime<Utc> {
+        fn to_epoch_seconds(&self) -> Option<i64> {
+            Some(self.timestamp())
+        }
+
+        fn from_epoch_seconds(seconds: i64) -> Option<Self> {
+            DateTime::from_timestamp(seconds, 0)
+        }
+    }
+
+    impl EpochMillis for DateTime<Utc> {
+        fn to_epoch_millis(&self) -> Option<i64> {
+            Some(self.timestamp_millis())
+        }
+
+        fn from_epoch_millis(millis: i64) -> Option<Self> {
+            DateTime::from_timestamp_millis(millis)
+        }
+    }
+
+    impl Iso8601 for DateTime<Utc> {
+        fn to_iso8601(&self) -> String {
+            self.to_rfc3339_opts(SecondsFormat::Millis, true)
+        }
+
+        fn from_iso8601(iso8601: &str) -> Option<Self> {
+            DateTime::parse_from_rfc3339(iso8601)
+                .ok()
+                .map(|time| time.with_timezone(&Utc))
+        }
+    }
+
+    /// A date is stored as its midnight in UTC.
+    impl EpochSeconds for NaiveDate {
+        fn to_epoch_seconds(&self) -> Option<i64> {
+            Some(midnight(self).timestamp())
+        }
+
+        fn from_epoch_seconds(seconds: i64) -> Option<Self> {
+            DateTime::from_timestamp(seconds, 0).map(|time| time.date_naive())
+        }
+    }
+
+    /// A date is stored as its midnight in UTC.
+    impl EpochMillis for NaiveDate {
+        fn to_epoch_millis(&self) -> Option<i64> {
+            Some(midnight(self).timestamp_millis())
+        }
+
+        fn from_epoch_millis(millis: i64) -> Option<Self> {
+            DateTime::from_timestamp_millis(millis).map(|time| time.date_naive())
+        }
+    }
+
+    impl Iso8601 for NaiveDate {
+        fn to_iso8601(&self) -> String {
+            self.format("%Y-%m-%d").to_string()
+        }
+
+        fn from_iso8601(iso8601: &str) -> Option<Self> {
+            NaiveDate::parse_from_str(iso8601, "%Y-%m-%d").ok()
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+mod time_impls {
+    use std::convert::TryFrom;
+
+    use time::format_description::well_known::Rfc3339;
+    use time::{OffsetDateTime, UtcOffset};
+
+    use super::{EpochMillis, EpochSeconds, Iso8601};
+
+    impl EpochSeconds for OffsetDateTime {
+        fn to_epoch_seconds(&self) -> Option<i64> {
+            Some(self.unix_timestamp())
+        }
+
+        fn from_epoch_seconds(seconds: i64) -> Option<Self> {
+            OffsetDateTime::from_unix_timestamp(seconds).ok()
+        }
+    }
+
+    impl EpochMillis for OffsetDateTime {
+        fn to_epoch_millis(&self) -> Option<i64> {
+            i64::try_from(self.unix_timestamp_nanos().div_euclid(1_000_000)).ok()
+        }
+
+        fn from_epoch_millis(millis: i64) -> Option<Self> {
+            OffsetDateTime::from_unix_timestamp_nanos(i128::from(millis) * 1_000_000).ok()
+        }
+    }
+
+    impl Iso8601 for OffsetDateTime {
+        fn to_iso8601(&self) -> String {
+            let utc = self.to_offset(UtcOffset::UTC);
+            format!(
+                "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+                utc.year(),
+                u8::from(utc.month()),
+                utc.day(),
+                utc.hour(),
+                utc.minute(),
+                utc.second(),
+                utc.millisecond()
+            )
+        }
+
+        fn from_iso8601(iso8601: &str) -> Option<Self> {
+            OffsetDateTime::parse(iso8601, &Rfc3339)
+                .ok()
+                .map(|time| time.to_offset(UtcOffset::UTC))
+        }
+    }
+}
+
+/// Serialize a time as a number of seconds since the epoch, to use with
+/// `#[serde(with = "serde_dynamodb::timestamp::epoch_seconds")]`.
+///
+/// Use [`epoch_seconds::option`](option/index.html) for an `Option`.
+pub mod epoch_seconds {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use super::EpochSeconds;
+
+    /// Serialize `time` as a number of seconds since the epoch.
+    ///
+    /// # Errors
+    ///
+    /// This fails if the time can't be represented as an `i64`, or if the
+    /// serializer fails.
+    pub fn serialize<T, S>(time: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: EpochSeconds,
+        S: Serializer,
+    {
+        serializer.serialize_i64(time.to_epoch_seconds().ok_or_else(out_of_range)?)
+    }
+
+    fn out_of_range<E: serde::ser::Error>() -> E {
+        E::custom("time out of range")
+    }
+
+    /// Deserialize a time from a number of seconds since the epoch.
+    ///
+    /// # Errors
+    ///
+    /// This fails if the value isn't an integer, or is out of range.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: EpochSeconds,
+        D: Deserializer<'de>,
+    {
+        let seconds = i64::deserialize(deserializer)?;
+        T::from_epoch_seconds(seconds)
+            .ok_or_else(|| serde::de::Error::custom(format!("time out of range: {}", seconds)))
+    }
+
+    /// Serialize an optional time as a number of seconds since the epoch, to use
+    /// with `#[serde(with = "serde_dynamodb::timestamp::epoch_seconds::option")]`.
+    pub mod option {
+        use serde::{Deserialize, Deserializer, Serializer};
+
+        use super::super::EpochSeconds;
+
+        /// Serialize `time` as a number of seconds since the epoch, if it is set.
+        ///
+        /// # Errors
+        ///
+        /// This fails if the time can't be represented as an `i64`, or if the
+        /// serializer fails.
+        pub fn serialize<T, S>(time: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            T: EpochSeconds,
+            S: Serializer,
+        {
+            match time {
+                Some(time) => serializer
+                    .serialize_some(&time.to_epoch_seconds().ok_or_else(super::out_of_range)?),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        /// Deserialize an optional time from a number of seconds since the epoch.
+        ///
+        /// # Errors
+        ///
+        /// This fails if the value is set and isn't an integer, or is out of range.
+        pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+        where
+            T: EpochSeconds,
+            D: Deserializer<'de>,
+        {
+            match Option::<i64>::deserialize(deserializer)? {
+                Some(seconds) => T::from_epoch_seconds(seconds).map(Some).ok_or_else(|| {
+                    serde::de::Error::custom(format!("time out of range: {}", seconds))
+                }),
+                None => Ok(None),
+            }
+        }
+    }
+}
+
+/// Serialize a time as a number of milliseconds since the epoch, to use with
+/// `#[serde(with = "serde_dynamodb::timestamp::epoch_millis")]`.
+///
+/// Use [`epoch_millis::option`](option/index.html) for an `Option`.
+pub mod epoch_millis {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use super::EpochMillis;
+
+    /// Serialize `time` as a number of milliseconds since the epoch.
+    ///
+    /// # Errors
+    ///
+    /// This fails if the time can't be represented as an `i64`, or if the
+    /// serializer fails.
+    pub fn serialize<T, S>(time: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: EpochMillis,
+        S: Serializer,
+    {
+        serializer.serialize_i64(time.to_epoch_millis().ok_or_else(out_of_range)?)
+    }
+
+    fn out_of_range<E: serde::ser::Error>() -> E {
+        E::custom("time out of range")
+    }
+
+    /// Deserialize a time from a number of milliseconds since the epoch.
+    ///
+    /// # Errors
+    ///
+    /// This fails if the value isn't an integer, or is out of range.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: EpochMillis,
+        D: Deserializer<'de>,
+    {
+        let millis = i64::deserialize(deserializer)?;
+        T::from_epoch_millis(millis)
+            .ok_or_else(|| serde::de::Error::custom(format!("time out of range: {}", millis)))
+    }
+
+    /// Serialize an optional time as a number of milliseconds since the epoch, to
+    /// use with `#[serde(with = "serde_dynamodb::timestamp::epoch_millis::option")]`.
+    pub mod option {
+        use serde::{Deserialize, Deserializer, Serializer};
+
+        use super::super::EpochMillis;
+
+        /// Serialize `time` as a number of milliseconds since the epoch, if it is set.
+        ///
+        /// # Errors
+        ///
+        /// This fails if the time can't be represented as an `i64`, or if the
+        /// serializer fails.
+        pub fn serialize<T, S>(time: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            T: EpochMillis,
+            S: Serializer,
+        {
+            match time {
+                Some(time) => serializer
+                    .serialize_some(&time.to_epoch_millis().ok_or_else(super::out_of_range)?),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        /// Deserialize an optional time from a number of milliseconds since the epoch.
+        ///
+        /// # Errors
+        ///
+        /// This fails if the value is set and isn't an integer, or is out of range.
+        pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+        where
+            T: EpochMillis,
+            D: Deserializer<'de>,
+        {
+            match Option::<i64>::deserialize(deserializer)? {
+                Some(millis) => T::from_epoch_millis(millis).map(Some).ok_or_else(|| {
+                    serde::de::Error::custom(format!("time out of range: {}", millis))
+                }),
+                None => Ok(None),
+            }
+        }
+    }
+}
+
+/// Serialize a time as an ISO 8601 string, to use with
+/// `#[serde(with = "serde_dynamodb::timestamp::iso8601")]`.
+///
+/// Use [`iso8601::option`](option/index.html) for an `Option`.
+pub mod iso8601 {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use super::Iso8601;
+
+    /// Serialize `time` as an ISO 8601 string.
+    ///
+    /// # Errors
+    ///
+    /// This fails if the serializer fails.
+    pub fn serialize<T, S>(time: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Iso8601,
+        S: Serializer,
+    {
+        serializer.serialize_str(&time.to_iso8601())
+    }
+
+    /// Deserialize a time from an ISO 8601 string.
+    ///
+    /// # Errors
+    ///
+    /// This fails if the value isn't a valid ISO 8601 string.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Iso8601,
+        D: Deserializer<'de>,
+    {
+        let iso8601 = String::deserialize(deserializer)?;
+        T::from_iso8601(&iso8601)
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid time: {}", iso8601)))
+    }
+
+    /// Serialize an optional time as an ISO 8601 string, to use with
+    /// `#[serde(with = "serde_dynamodb::timestamp::iso8601::option")]`.
+    pub mod option {
+        use serde::{Deserialize, Deserializer, Serializer};
+
+        use super::super::Iso8601;
+
+        /// Serialize `time` as an ISO 8601 string, if it is set.
+        ///
+        /// # Errors
+        ///
+        /// This fails if the serializer fails.
+        pub fn serialize<T, S>(time: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            T: Iso8601,
+            S: Serializer,
+        {
+            match time {
+                Some(time) => serializer.serialize_some(&time.to_iso8601()),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        /// Deserialize an optional time from an ISO 8601 string.
+        ///
+        /// # Errors
+        ///
+        /// This fails if the value is set and isn't a valid ISO 8601 string.
+        pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+        where
+            T: Iso8601,
+            D: Deserializer<'de>,
+        {
+            match Option::<String>::deserialize(deserializer)? {
+                Some(iso8601) => T::from_iso8601(&iso8601)
+                    .map(Some)
+                    .ok_or_else(|| serde::de::Error::custom(format!("invalid time: {}", iso8601))),
+                None => Ok(None),
+            }
+        }
+    }
+}
@@ -0,0 +1,314 @@
+//! Trace the attributes read by a `Deserialize` implementation, without any data.
+
+use serde::de::value::{MapDeserializer, SeqDeserializer, StrDeserializer};
+use serde::de::{DeserializeOwned, IntoDeserializer, Visitor};
+
+use crate::error::{Error, Result};
+use crate::path::AttributePath;
+
+/// The type of a scalar attribute, as used in key schemas.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ScalarType {
+    /// A string
+    S,
+    /// A number
+    N,
+    /// A binary value
+    B,
+}
+
+impl ScalarType {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            ScalarType::S => "S",
+            ScalarType::N => "N",
+            ScalarType::B => "B",
+        }
+    }
+}
+
+/// What was learned by tracing the `Deserialize` implementation of a type.
+#[derive(Debug, Default)]
+pub(crate) struct Trace {
+    /// The paths of the attributes that are read, with fields of nested structs
+    /// replaced by the paths to their own fields.
+    pub(crate) leaves: Vec<AttributePath>,
+    /// The type of the attributes that are read as scalars.
+    pub(crate) types: Vec<(AttributePath, ScalarType)>,
+    structs: Vec<&'static str>,
+    /// The fields that are not given to the visitors, as they failed in a
+    /// previous run.
+    skipped: Vec<AttributePath>,
+    /// The first field whose value failed to be deserialized.
+    failed: Option<AttributePath>,
+}
+
+impl Trace {
+    /// The type of the scalar attribute at `path`, if it is one.
+    pub(crate) fn scalar_type(&self, path: &AttributePath) -> Option<ScalarType> {
+        self.types
+            .iter()
+            .find(|(traced, _)| traced == path)
+            .map(|(_, scalar_type)| *scalar_type)
+    }
+}
+
+/// Trace the `Deserialize` implementation of `T`.
+///
+/// The fields of structs are found through the `fields` given to
+/// `deserialize_struct`, so serde renames are honored, and default values are
+/// given to the visitors of the other types. A field that can't be traced, such as
+/// an enum, or that rejects its default value, such as a date read from a string,
+/// stops the deserialization: it is traced again without giving that field to the
+/// visitor, so that the following fields are traced too. The error is returned if
+/// `T` itself can't be traced, or if the deserialization failed for another reason.
+pub(crate) fn trace<T: DeserializeOwned>() -> (Trace, Result<()>) {
+    let mut skipped = vec![];
+    let mut failed_types = vec![];
+    loop {
+        let mut trace = Trace {
+            skipped: skipped.clone(),
+            ..Default::default()
+        };
+        let result = T::deserialize(Tracer {
+            trace: &mut trace,
+            path: AttributePath::default(),
+        })
+        .map(|_| ());
+        match trace.failed.take() {
+            Some(failed) if result.is_err() => {
+                // the type of the failed field was recorded before its value was rejected
+                failed_types.extend(
+                    trace
+                        .types
+                        .into_iter()
+                        .filter(|(path, _)| path.elements.starts_with(&failed.elements)),
+                );
+                skipped.push(failed);
+            }
+            _ => {
+                trace.types.extend(failed_types);
+                return (trace, result);
+            }
+        }
+    }
+}
+
+struct Tracer<'a> {
+    trace: &'a mut Trace,
+    path: AttributePath,
+}
+
+impl Tracer<'_> {
+    fn record(&mut self, scalar_type: Option<ScalarType>) {
+        if let Some(scalar_type) = scalar_type {
+            self.trace.types.push((self.path.clone(), scalar_type));
+        }
+    }
+
+    fn untraceable(&self) -> Error {
+        Error {
+            message: format!("can't trace the type of {}", self.path),
+        }
+    }
+}
+
+macro_rules! impl_deserialize_default {
+    ($method:ident, $visit:ident, $value:expr, $scalar_type:expr) => {
+        fn $method<V: Visitor<'de>>(mut self, visitor: V) -> Result<V::Value> {
+            self.record($scalar_type);
+            visitor.$visit($value)
+        }
+    };
+}
+
+impl<'de> serde::Deserializer<'de> for Tracer<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value> {
+        Err(self.untraceable())
+    }
+
+    impl_deserialize_default!(deserialize_bool, visit_bool, false, None);
+    impl_deserialize_default!(deserialize_i8, visit_i8, 0, Some(ScalarType::N));
+    impl_deserialize_default!(deserialize_i16, visit_i16, 0, Some(ScalarType::N));
+    impl_deserialize_default!(deserialize_i32, visit_i32, 0, Some(ScalarType::N));
+    impl_deserialize_default!(deserialize_i64, visit_i64, 0, Some(ScalarType::N));
+    impl_deserialize_default!(deserialize_u8, visit_u8, 0, Some(ScalarType::N));
+    impl_deserialize_default!(deserialize_u16, visit_u16, 0, Some(ScalarType::N));
+    impl_deserialize_default!(deserialize_u32, visit_u32, 0, Some(ScalarType::N));
+    impl_deserialize_default!(deserialize_u64, visit_u64, 0, Some(ScalarType::N));
+    impl_deserialize_default!(deserialize_f32, visit_f32, 0.0, Some(ScalarType::N));
+    impl_deserialize_default!(deserialize_f64, visit_f64, 0.0, Some(ScalarType::N));
+    impl_deserialize_default!(deserialize_char, visit_char, '\0', Some(ScalarType::S));
+    impl_deserialize_default!(deserialize_str, visit_borrowed_str, "", Some(ScalarType::S));
+    impl_deserialize_default!(
+        deserialize_string,
+        visit_borrowed_str,
+        "",
+        Some(ScalarType::S)
+    );
+    impl_deserialize_default!(
+        deserialize_bytes,
+        visit_borrowed_bytes,
+        &[],
+        Some(ScalarType::B)
+    );
+    impl_deserialize_default!(
+        deserialize_byte_buf,
+        visit_borrowed_bytes,
+        &[],
+        Some(ScalarType::B)
+    );
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        mut self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        if name == crate::number::TOKEN {
+            self.record(Some(ScalarType::N));
+            let number: StrDeserializer<Error> = "0".into_deserializer();
+            return visitor.visit_newtype_struct(number);
+        }
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_seq(SeqDeserializer::<_, Error>::new(std::iter::empty::<()>()))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, _visitor: V) -> Result<V::Value> {
+        Err(self.untraceable())
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        _visitor: V,
+    ) -> Result<V::Value> {
+        Err(self.untraceable())
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        if self.path.elements.is_empty() {
+            // a struct with flattened fields is read as a map, whose keys are unknown
+            return Err(Error {
+                message: "can't trace a map or a struct with flattened fields".to_owned(),
+            });
+        }
+        visitor.visit_map(MapDeserializer::<_, Error>::new(
+            std::iter::empty::<((), ())>(),
+        ))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        if self.trace.structs.contains(&name) {
+            // a recursive struct is read as a whole
+            return Err(self.untraceable());
+        }
+
+        let path = &self.path;
+        let children = fields.iter().map(|field| path.with_attribute(field));
+        match self.trace.leaves.iter().position(|leaf| leaf == path) {
+            Some(i) => {
+                self.trace.leaves.splice(i..=i, children);
+            }
+            None => self.trace.leaves.extend(children),
+        }
+
+        self.trace.structs.push(name);
+        let result = visitor.visit_map(Fields {
+            trace: &mut *self.trace,
+            path: &self.path,
+            fields: fields.iter(),
+            current: "",
+        });
+        self.trace.structs.pop();
+        result
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value> {
+        Err(self.untraceable())
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value> {
+        Err(self.untraceable())
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+}
+
+struct Fields<'a> {
+    trace: &'a mut Trace,
+    path: &'a AttributePath,
+    fields: std::slice::Iter<'static, &'static str>,
+    current: &'static str,
+}
+
+impl<'de> serde::de::MapAccess<'de> for Fields<'_> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: serde::de::DeserializeSeed<'de>,
+    {
+        let path = self.path;
+        let skipped = &self.trace.skipped;
+        match self
+            .fields
+            .find(|field| !skipped.contains(&path.with_attribute(field)))
+        {
+            Some(field) => {
+                self.current = field;
+                let key: StrDeserializer<Error> = field.into_deserializer();
+                seed.deserialize(key).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: serde::de::DeserializeSeed<'de>,
+    {
+        let path = self.path.with_attribute(self.current);
+        let result = seed.deserialize(Tracer {
+            trace: &mut *self.trace,
+            path: path.clone(),
+        });
+        if result.is_err() && self.trace.failed.is_none() {
+            self.trace.failed = Some(path);
+        }
+        result
+    }
+}
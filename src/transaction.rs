@@ -0,0 +1,391 @@
+//! Write items together with `TransactWriteItems`.
+
+use std::collections::HashMap;
+
+use rusoto_core::RusotoError;
+use rusoto_dynamodb::{
+    AttributeValue, CancellationReason, ConditionCheck, Delete, DynamoDb, Put, TransactWriteItem,
+    TransactWriteItemsError, TransactWriteItemsInput, Update as UpdateAction,
+};
+
+use crate::batch_write::item_size;
+use crate::dynamodb::ser::to_hashmap;
+use crate::error::{Error, RequestError, Result};
+use crate::expression::{Condition, ExpressionAttributes, Update};
+use crate::item::DynamoItem;
+
+/// The maximum number of operations in a transaction.
+const MAX_OPERATIONS: usize = 100;
+/// The maximum size of a transaction.
+const MAX_SIZE: usize = 4 * 1024 * 1024;
+
+type Item = HashMap<String, AttributeValue>;
+
+#[derive(Debug)]
+enum Kind {
+    Put(Result<Item>),
+    Update(Result<Item>, Update),
+    Delete(Result<Item>),
+    Check(Result<Item>),
+}
+
+#[derive(Debug)]
+struct Operation {
+    table_name: &'static str,
+    kind: Kind,
+    condition: Option<Condition>,
+}
+
+impl Operation {
+    fn build(self) -> Result<(TransactWriteItem, usize)> {
+        let Operation {
+            table_name,
+            kind,
+            condition,
+        } = self;
+        let table_name = table_name.to_owned();
+        let (mut attributes, update_expression) = match &kind {
+            Kind::Update(_, update) => (
+                update.attributes.clone(),
+                update.update_expression.clone().ok_or_else(|| Error {
+                    message: format!(
+                        "an update of an item of {} has nothing to update",
+                        table_name
+                    ),
+                })?,
+            ),
+            _ => (ExpressionAttributes::new(), String::new()),
+        };
+        let condition_expression = condition
+            .map(|condition| condition.build(&mut attributes))
+            .transpose()?;
+        let size = attributes.values.values().map(value_size).sum::<usize>();
+        let names = attributes.names();
+        let values = attributes.values();
+
+        let mut operation = TransactWriteItem::default();
+        let operation_size = match kind {
+            Kind::Put(item) => {
+                let item = item?;
+                let size = item_size(&item);
+                operation.put = Some(Put {
+                    item,
+                    table_name,
+                    condition_expression,
+                    expression_attribute_names: names,
+                    expression_attribute_values: values,
+                    ..Default::default()
+                });
+                size
+            }
+            Kind::Update(key, _) => {
+                let key = key?;
+                let size = item_size(&key);
+                operation.update = Some(UpdateAction {
+                    key,
+                    table_name,
+                    update_expression,
+                    condition_expression,
+                    expression_attribute_names: names,
+                    expression_attribute_values: values,
+                    ..Default::default()
+                });
+                size
+            }
+            Kind::Delete(key) => {
+                let key = key?;
+                let size = item_size(&key);
+                operation.delete = Some(Delete {
+                    key,
+                    table_name,
+                    condition_expression,
+                    expression_attribute_names: names,
+                    expression_attribute_values: values,
+                    ..Default::default()
+                });
+                size
+            }
+            Kind::Check(key) => {
+                let key = key?;
+                let size = item_size(&key);
+                operation.condition_check = Some(ConditionCheck {
+                    key,
+                    table_name,
+                    condition_expression: condition_expression.ok_or_else(|| Error {
+                        message: String::from("a condition check needs a condition"),
+                    })?,
+                    expression_attribute_names: names,
+                    expression_attribute_values: values,
+                    ..Default::default()
+                });
+                size
+            }
+        };
+        Ok((operation, size + operation_size))
+    }
+}
+
+fn value_size(value: &AttributeValue) -> usize {
+    let mut item = HashMap::new();
+    item.insert(String::new(), value.clone());
+    item_size(&item)
+}
+
+/// An error of a transaction.
+#[derive(Debug)]
+pub enum TransactionError {
+    /// The transaction was canceled, with the reason of each operation in order:
+    /// its code is `None` if the operation didn't fail, or a reason such as
+    /// `ConditionalCheckFailed` or `TransactionConflict`.
+    ///
+    /// The `TransactWriteItemsError` of rusoto only keeps the message of the
+    /// error, so the reasons only have the codes listed in that message, without
+    /// their message or item.
+    Canceled(Vec<CancellationReason>),
+    /// The transaction couldn't be built, or the request failed.
+    Request(RequestError<TransactWriteItemsError>),
+}
+impl From<Error> for TransactionError {
+    fn from(error: Error) -> Self {
+        TransactionError::Request(RequestError::Serde(error))
+    }
+}
+impl From<RusotoError<TransactWriteItemsError>> for TransactionError {
+    fn from(error: RusotoError<TransactWriteItemsError>) -> Self {
+        match error {
+            RusotoError::Service(TransactWriteItemsError::TransactionCanceled(message)) => {
+                TransactionError::Canceled(cancellation_reasons(&message))
+            }
+            error => TransactionError::Request(RequestError::Rusoto(error)),
+        }
+    }
+}
+impl std::fmt::Display for TransactionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TransactionError::Canceled(reasons) => {
+                write!(f, "transaction canceled")?;
+                for (index, reason) in reasons.iter().enumerate() {
+                    match reason.code.as_deref() {
+                        None | Some("None") => {}
+                        Some(code) => write!(f, ", operation {}: {}", index, code)?,
+                    }
+                }
+                Ok(())
+            }
+            TransactionError::Request(error) => std::fmt::Display::fmt(error, f),
+        }
+    }
+}
+impl std::error::Error for TransactionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TransactionError::Canceled(_) => None,
+            TransactionError::Request(error) => Some(error),
+        }
+    }
+}
+
+/// The reason of each operation, from the message of a
+/// `TransactionCanceledException`.
+///
+/// The message ends with the code of each operation, in order, such as
+/// `[None, ConditionalCheckFailed]`, `None` being for the operations that didn't fail.
+fn cancellation_reasons(message: &str) -> Vec<CancellationReason> {
+    let codes = match (message.rfind('['), message.rfind(']')) {
+        (Some(start), Some(end)) if start < end => &message[start + 1..end],
+        _ => return vec![],
+    };
+    codes
+        .split(',')
+        .map(|code| CancellationReason {
+            code: Some(code.trim().to_owned()),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Puts, updates, deletes and condition checks on items, that succeed or fail
+/// together.
+///
+/// Items and keys are serialized with [`to_hashmap`](fn.to_hashmap.html), and each
+/// operation can have a [`Condition`](expression/struct.Condition.html), set with
+/// [`condition`](#method.condition) after adding the operation.
+///
+/// Unlike a [`Table`](struct.Table.html), a transaction neither checks nor
+/// increments the version of the items of a versioned table, and doesn't set their
+/// creation or update time: to lock an item optimistically, add a condition on its
+/// version and update it.
+///
+/// ```rust,no_run
+/// use rusoto_core::Region;
+/// use rusoto_dynamodb::DynamoDbClient;
+/// use serde::{Deserialize, Serialize};
+/// use serde_dynamodb::expression::{Condition, Update};
+/// use serde_dynamodb::Transaction;
+/// use serde_dynamodb_derive::DynamoItem;
+///
+/// #[derive(Serialize, Deserialize, DynamoItem)]
+/// #[dynamodb(table = "orders")]
+/// struct Order {
+///     #[dynamodb(partition_key)]
+///     id: String,
+///     total: u64,
+/// }
+///
+/// #[derive(Clone, Serialize, Deserialize, DynamoItem)]
+/// #[dynamodb(table = "accounts")]
+/// struct Account {
+///     #[dynamodb(partition_key)]
+///     id: String,
+///     balance: u64,
+/// }
+///
+/// # async fn run(order: Order, before: Account) -> Result<(), serde_dynamodb::TransactionError> {
+/// let client = DynamoDbClient::new(Region::UsEast1);
+/// let after = Account { balance: before.balance - order.total, ..before.clone() };
+/// Transaction::new()
+///     .put(&order)
+///     .condition(Condition::attribute_not_exists("id"))
+///     .update(&after, Update::between(&before, &after, &["id"])?)
+///     .condition(Condition::eq("balance", &before.balance))
+///     .send(&client)
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct Transaction {
+    operations: Vec<Operation>,
+    client_request_token: Option<String>,
+    /// A condition set before any operation, reported when building.
+    misplaced_condition: bool,
+}
+
+impl Transaction {
+    /// An empty transaction.
+    pub fn new() -> Self {
+        Transaction::default()
+    }
+
+    fn push(mut self, table_name: &'static str, kind: Kind) -> Self {
+        self.operations.push(Operation {
+            table_name,
+            kind,
+            condition: None,
+        });
+        self
+    }
+
+    /// Put `item` in its table.
+    pub fn put<T>(self, item: &T) -> Self
+    where
+        T: DynamoItem + serde::Serialize,
+    {
+        self.push(T::TABLE_NAME, Kind::Put(to_hashmap(item)))
+    }
+
+    /// Update the item with the key of `item` with `update`.
+    pub fn update<T: DynamoItem>(self, item: &T, update: Update) -> Self {
+        self.push(T::TABLE_NAME, Kind::Update(item.try_key(), update))
+    }
+
+    /// Delete the item of the table of `T` with the key `key`.
+    pub fn delete<T: DynamoItem>(self, key: &T::Key) -> Self {
+        self.push(T::TABLE_NAME, Kind::Delete(to_hashmap(key)))
+    }
+
+    /// Check `condition` on the item of the table of `T` with the key `key`, without
+    /// writing it.
+    pub fn check<T: DynamoItem>(self, key: &T::Key, condition: Condition) -> Self {
+        self.push(T::TABLE_NAME, Kind::Check(to_hashmap(key)))
+            .condition(condition)
+    }
+
+    /// Set the condition of the last operation, replacing any previous condition.
+    ///
+    /// Without any operation yet, the transaction fails to build.
+    pub fn condition(mut self, condition: Condition) -> Self {
+        match self.operations.last_mut() {
+            Some(operation) => operation.condition = Some(condition),
+            None => self.misplaced_condition = true,
+        }
+        self
+    }
+
+    /// Make the transaction idempotent: sending it again with the same token within
+    /// ten minutes doesn't write again.
+    pub fn client_request_token(mut self, client_request_token: String) -> Self {
+        self.client_request_token = Some(client_request_token);
+        self
+    }
+
+    /// The number of operations.
+    pub fn len(&self) -> usize {
+        self.operations.len()
+    }
+
+    /// Whether there is no operation.
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// The `TransactWriteItemsInput` of this transaction.
+    ///
+    /// # Errors
+    ///
+    /// This fails if an item, a key or a value couldn't be serialized, if an update
+    /// has nothing to update, if a condition was set before any operation, or if
+    /// the transaction has more than 100 operations or is bigger than 4MB.
+    pub fn build(self) -> Result<TransactWriteItemsInput> {
+        if self.misplaced_condition {
+            return Err(Error {
+                message: String::from("a condition was set before any operation"),
+            });
+        }
+        if self.operations.len() > MAX_OPERATIONS {
+            return Err(Error {
+                message: format!(
+                    "a transaction can't have more than {} operations, this one has {}",
+                    MAX_OPERATIONS,
+                    self.operations.len()
+                ),
+            });
+        }
+        let mut size = 0;
+        let mut transact_items = vec![];
+        for operation in self.operations {
+            let (operation, operation_size) = operation.build()?;
+            size += operation_size;
+            transact_items.push(operation);
+        }
+        if size > MAX_SIZE {
+            return Err(Error {
+                message: format!(
+                    "a transaction can't be bigger than 4MB, this one is {} bytes",
+                    size
+                ),
+            });
+        }
+        Ok(TransactWriteItemsInput {
+            transact_items,
+            client_request_token: self.client_request_token,
+            ..Default::default()
+        })
+    }
+
+    /// Send the transaction.
+    ///
+    /// # Errors
+    ///
+    /// This fails if the transaction couldn't be built, if it was canceled, or if
+    /// the request failed.
+    pub async fn send<C>(self, client: &C) -> std::result::Result<(), TransactionError>
+    where
+        C: DynamoDb + Sync + ?Sized,
+    {
+        let input = self.build()?;
+        client.transact_write_items(input).await?;
+        Ok(())
+    }
+}
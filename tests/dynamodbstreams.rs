@@ -33,10 +33,12 @@ fn can_serialize_struct() {
 
 #[test]
 fn can_deserialize_struct() {
+    #[allow(dead_code)]
     #[derive(Deserialize, Debug)]
     struct Internal {
         k: i32,
     }
+    #[allow(dead_code)]
     #[derive(Deserialize, Debug)]
     struct Basic {
         i: i32,
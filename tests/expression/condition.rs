@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+
+use rusoto_dynamodb::AttributeValue;
+use serde::Serialize;
+use serde_dynamodb::expression::{query_input, Condition, ExpressionAttributes};
+use serde_dynamodb::path::AttributePath;
+
+fn n(value: &str) -> AttributeValue {
+    AttributeValue {
+        n: Some(String::from(value)),
+        ..Default::default()
+    }
+}
+
+fn s(value: &str) -> AttributeValue {
+    AttributeValue {
+        s: Some(String::from(value)),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn can_build_key_condition_and_filter() {
+    let input = query_input(
+        String::from("orders"),
+        Condition::eq("id", "42")
+            .and(Condition::between("created", &10, &20))
+            .and(Condition::begins_with("sk", "ORDER#")),
+        Some(
+            Condition::attribute_exists(AttributePath::attribute("data").with_attribute("city"))
+                .or(!Condition::contains("tags", "archived")),
+        ),
+    )
+    .unwrap();
+
+    assert_eq!(input.table_name, "orders");
+    assert_eq!(
+        input.key_condition_expression.as_deref(),
+        Some("id = :v0 AND created BETWEEN :v1 AND :v2 AND begins_with (sk, :v3)")
+    );
+    assert_eq!(
+        input.filter_expression.as_deref(),
+        Some("attribute_exists (#n0.city) OR NOT (contains (tags, :v4))")
+    );
+
+    let mut names = HashMap::new();
+    names.insert(String::from("#n0"), String::from("data"));
+    assert_eq!(input.expression_attribute_names, Some(names));
+
+    let mut values = HashMap::new();
+    values.insert(String::from(":v0"), s("42"));
+    values.insert(String::from(":v1"), n("10"));
+    values.insert(String::from(":v2"), n("20"));
+    values.insert(String::from(":v3"), s("ORDER#"));
+    values.insert(String::from(":v4"), s("archived"));
+    assert_eq!(input.expression_attribute_values, Some(values));
+}
+
+#[test]
+fn can_build_size_and_in_conditions() {
+    let mut attributes = ExpressionAttributes::new();
+    let expression = Condition::size("items")
+        .ge(&2)
+        .and(Condition::is_in("status", &["open", "pending"]).or(Condition::ne("status", "closed")))
+        .build(&mut attributes)
+        .unwrap();
+
+    assert_eq!(
+        expression,
+        "size (#n0) >= :v0 AND (#n1 IN (:v1, :v2) OR #n1 <> :v3)"
+    );
+    assert_eq!(attributes.names.len(), 2);
+    assert_eq!(attributes.values[":v2"], s("pending"));
+}
+
+#[test]
+fn can_use_structured_values() {
+    #[derive(Serialize)]
+    struct Point {
+        x: i32,
+    }
+
+    let mut attributes = ExpressionAttributes::new();
+    Condition::eq("point", &Point { x: 1 })
+        .build(&mut attributes)
+        .unwrap();
+
+    let mut point = HashMap::new();
+    point.insert(String::from("x"), n("1"));
+    assert_eq!(
+        attributes.values[":v0"],
+        AttributeValue {
+            m: Some(point),
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn serialization_errors_are_returned_on_build() {
+    let mut key = HashMap::new();
+    key.insert(vec![1u8], 1);
+
+    let mut attributes = ExpressionAttributes::new();
+    assert!(Condition::eq("map", &key).build(&mut attributes).is_err());
+}
+
+#[test]
+fn in_needs_at_least_one_value() {
+    let mut attributes = ExpressionAttributes::new();
+    let values: Vec<&str> = vec![];
+    assert!(Condition::is_in("status", values)
+        .build(&mut attributes)
+        .is_err());
+}
@@ -0,0 +1,9 @@
+//! The expressions built for conditions, updates, projections and queries.
+
+#![cfg(feature = "rusoto_dynamodb")]
+
+mod condition;
+mod projection;
+mod query_input;
+mod reserved_words;
+mod update_expression;
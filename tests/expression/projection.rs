@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use serde_dynamodb::expression::{projection_for, ExpressionAttributes};
+use serde_dynamodb::path::AttributePath;
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct Geo {
+    lat: f64,
+    lng: f64,
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct Address {
+    city: String,
+    geo: Geo,
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct Profile {
+    id: String,
+    #[serde(rename = "name")]
+    display_name: String,
+    address: Option<Address>,
+    tags: Vec<String>,
+    scores: HashMap<String, u32>,
+    #[serde(skip)]
+    cached: bool,
+}
+
+#[test]
+fn can_project_struct_fields() {
+    let projection = projection_for::<Profile>().unwrap();
+
+    assert_eq!(
+        projection.paths,
+        vec![
+            AttributePath::attribute("id"),
+            AttributePath::attribute("name"),
+            AttributePath::attribute("address").with_attribute("city"),
+            AttributePath::attribute("address")
+                .with_attribute("geo")
+                .with_attribute("lat"),
+            AttributePath::attribute("address")
+                .with_attribute("geo")
+                .with_attribute("lng"),
+            AttributePath::attribute("tags"),
+            AttributePath::attribute("scores"),
+        ]
+    );
+}
+
+#[test]
+fn can_share_placeholders_with_other_expressions() {
+    let mut attributes = ExpressionAttributes::new();
+    let status = attributes.name("status");
+    let expression = projection_for::<Profile>().unwrap().build(&mut attributes);
+
+    assert_eq!(status, "#n0");
+    assert_eq!(
+        expression,
+        "id, #n1, address.city, address.geo.lat, address.geo.lng, tags, scores"
+    );
+    assert_eq!(attributes.names["#n1"], "name");
+}
+
+#[test]
+fn recursive_structs_are_projected_as_a_whole() {
+    #[derive(Deserialize)]
+    #[allow(dead_code)]
+    struct Node {
+        name: String,
+        parent: Option<Box<Node>>,
+    }
+
+    assert_eq!(
+        projection_for::<Node>().unwrap().paths,
+        vec![
+            AttributePath::attribute("name"),
+            AttributePath::attribute("parent")
+        ]
+    );
+}
+
+#[test]
+fn only_structs_can_be_projected() {
+    assert!(projection_for::<String>().is_err());
+}
+
+#[test]
+fn flattened_structs_cant_be_projected() {
+    #[derive(Deserialize)]
+    #[allow(dead_code)]
+    struct Flattened {
+        id: String,
+        #[serde(flatten)]
+        others: HashMap<String, String>,
+    }
+
+    assert_eq!(
+        projection_for::<Flattened>().unwrap_err().message,
+        "can't trace a map or a struct with flattened fields"
+    );
+}
@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+
+use rusoto_dynamodb::AttributeValue;
+use serde::Serialize;
+use serde_dynamodb::ToQueryInput;
+use serde_dynamodb_derive::ToQueryInput;
+
+fn s(value: &str) -> AttributeValue {
+    AttributeValue {
+        s: Some(String::from(value)),
+        ..Default::default()
+    }
+}
+
+#[derive(Serialize, ToQueryInput)]
+#[allow(dead_code)]
+struct Legacy {
+    id: String,
+    #[serde(rename = "state")]
+    status: String,
+}
+
+#[derive(Serialize, ToQueryInput)]
+#[serde(rename_all = "camelCase")]
+#[dynamodb(index = "by_status", partition_key = "status", sort_key = "created_at")]
+#[allow(dead_code)]
+struct Task {
+    #[dynamodb(partition_key)]
+    project_id: String,
+    #[dynamodb(sort_key)]
+    id: String,
+    status: String,
+    created_at: u64,
+    #[serde(rename = "who")]
+    assignee: String,
+}
+
+#[test]
+fn without_keys_all_fields_are_in_the_key_condition() {
+    let input = LegacyQueryInput {
+        id: Some(String::from("1")),
+        status: Some(String::from("done")),
+    }
+    .to_query_input(String::from("tasks"));
+
+    assert_eq!(input.table_name, "tasks");
+    assert_eq!(input.index_name, None);
+    assert_eq!(
+        input.key_condition_expression.as_deref(),
+        Some("id = :v0 AND #n0 = :v1")
+    );
+    assert_eq!(input.filter_expression, None);
+    let mut names = HashMap::new();
+    names.insert(String::from("#n0"), String::from("state"));
+    assert_eq!(input.expression_attribute_names, Some(names));
+    let mut values = HashMap::new();
+    values.insert(String::from(":v0"), s("1"));
+    values.insert(String::from(":v1"), s("done"));
+    assert_eq!(input.expression_attribute_values, Some(values));
+}
+
+#[test]
+fn unset_fields_are_ignored() {
+    let input = LegacyQueryInput {
+        id: Some(String::from("1")),
+        ..Default::default()
+    }
+    .to_query_input(String::from("tasks"));
+
+    assert_eq!(input.key_condition_expression.as_deref(), Some("id = :v0"));
+    assert_eq!(input.expression_attribute_names, None);
+}
+
+#[test]
+fn other_fields_than_keys_are_filters() {
+    let input = TaskQueryInput {
+        project_id: Some(String::from("p")),
+        status: Some(String::from("done")),
+        assignee: Some(String::from("alice")),
+        ..Default::default()
+    }
+    .to_query_input(String::from("tasks"));
+
+    assert_eq!(input.index_name, None);
+    assert_eq!(
+        input.key_condition_expression.as_deref(),
+        Some("projectId = :v0")
+    );
+    assert_eq!(
+        input.filter_expression.as_deref(),
+        Some("#n0 = :v1 AND who = :v2")
+    );
+}
+
+#[test]
+fn index_is_selected_from_the_fields_set() {
+    let input = TaskQueryInput {
+        status: Some(String::from("done")),
+        created_at: Some(12),
+        id: Some(String::from("1")),
+        ..Default::default()
+    }
+    .to_query_input(String::from("tasks"));
+
+    assert_eq!(input.index_name.as_deref(), Some("by_status"));
+    assert_eq!(
+        input.key_condition_expression.as_deref(),
+        Some("#n0 = :v0 AND createdAt = :v1")
+    );
+    assert_eq!(input.filter_expression.as_deref(), Some("id = :v2"));
+}
+
+#[test]
+fn a_key_condition_is_needed() {
+    let input = TaskQueryInput {
+        id: Some(String::from("1")),
+        assignee: Some(String::from("alice")),
+        ..Default::default()
+    }
+    .try_to_query_input(String::from("tasks"));
+    assert!(input.is_err());
+    assert!(LegacyQueryInput::default()
+        .try_to_query_input(String::from("tasks"))
+        .is_err());
+}
+
+#[test]
+#[should_panic(expected = "without a key condition")]
+fn to_query_input_panics_without_key_condition() {
+    LegacyQueryInput::default().to_query_input(String::from("tasks"));
+}
+
+#[test]
+fn optional_fields_are_not_nested_options() {
+    #[derive(Serialize, ToQueryInput)]
+    #[allow(dead_code)]
+    struct Note {
+        #[dynamodb(partition_key)]
+        id: String,
+        author: Option<String>,
+    }
+
+    let input = NoteQueryInput {
+        id: Some(String::from("1")),
+        author: Some(String::from("alice")),
+    }
+    .to_query_input(String::from("notes"));
+
+    assert_eq!(input.filter_expression.as_deref(), Some("author = :v1"));
+    let mut values = HashMap::new();
+    values.insert(String::from(":v0"), s("1"));
+    values.insert(String::from(":v1"), s("alice"));
+    assert_eq!(input.expression_attribute_values, Some(values));
+}
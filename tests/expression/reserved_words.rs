@@ -0,0 +1,37 @@
+use serde_dynamodb::expression::{
+    escape_attribute_path, is_reserved_word, ExpressionAttributes, RESERVED_WORDS,
+};
+use serde_dynamodb::path::AttributePath;
+
+#[test]
+fn reserved_words_ignore_case() {
+    assert_eq!(RESERVED_WORDS.len(), 573);
+    assert!(is_reserved_word("status"));
+    assert!(is_reserved_word("Name"));
+    assert!(is_reserved_word("DATA"));
+    assert!(!is_reserved_word("title"));
+}
+
+#[test]
+fn only_names_that_need_it_are_escaped() {
+    let path = AttributePath::attribute("comments")
+        .with_index(2)
+        .with_attribute("user.name")
+        .with_attribute("first_seen");
+    let (expression, names) = escape_attribute_path(&path);
+
+    assert_eq!(expression, "comments[2].#n0.first_seen");
+    assert_eq!(names.len(), 1);
+    assert_eq!(names["#n0"], "user.name");
+}
+
+#[test]
+fn placeholders_are_reused_across_paths() {
+    let mut attributes = ExpressionAttributes::new();
+    assert_eq!(attributes.path(&AttributePath::attribute("1st")), "#n0");
+    assert_eq!(attributes.path(&AttributePath::attribute("_id")), "#n1");
+    assert_eq!(
+        attributes.path(&AttributePath::attribute("size").with_attribute("1st")),
+        "#n2.#n0"
+    );
+}
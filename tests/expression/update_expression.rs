@@ -0,0 +1,95 @@
+use std::collections::{BTreeSet, HashMap};
+
+use rusoto_dynamodb::AttributeValue;
+use serde::Serialize;
+use serde_dynamodb::expression::Update;
+
+#[derive(Serialize, Clone)]
+struct Task {
+    id: String,
+    status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    assignee: Option<String>,
+    tags: BTreeSet<String>,
+}
+
+fn task() -> Task {
+    Task {
+        id: String::from("1"),
+        status: String::from("todo"),
+        assignee: Some(String::from("alice")),
+        tags: BTreeSet::new(),
+    }
+}
+
+fn s(value: &str) -> AttributeValue {
+    AttributeValue {
+        s: Some(String::from(value)),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn same_items_have_no_update() {
+    let update = Update::between(&task(), &task(), &["id"]).unwrap();
+    assert!(update.is_empty());
+
+    let input = update.into_update_item_input();
+    assert_eq!(input.update_expression, None);
+    assert_eq!(input.expression_attribute_names, None);
+    assert_eq!(input.expression_attribute_values, None);
+}
+
+#[test]
+fn can_set_and_remove_attributes() {
+    let before = task();
+    let mut after = task();
+    after.id = String::from("2");
+    after.status = String::from("done");
+    after.assignee = None;
+
+    let input = Update::between(&before, &after, &["id"])
+        .unwrap()
+        .into_update_item_input();
+
+    assert_eq!(
+        input.update_expression.as_deref(),
+        Some("SET #n0 = :v0 REMOVE assignee")
+    );
+    let mut names = HashMap::new();
+    names.insert(String::from("#n0"), String::from("status"));
+    assert_eq!(input.expression_attribute_names, Some(names));
+    let mut values = HashMap::new();
+    values.insert(String::from(":v0"), s("done"));
+    assert_eq!(input.expression_attribute_values, Some(values));
+}
+
+#[test]
+fn can_add_and_delete_set_members() {
+    let set = |members: &[&str]| AttributeValue {
+        ss: Some(members.iter().map(|member| String::from(*member)).collect()),
+        ..Default::default()
+    };
+    let mut old = HashMap::new();
+    old.insert(String::from("labels"), set(&["a"]));
+    old.insert(String::from("owners"), set(&["a", "b"]));
+    old.insert(String::from("tags"), set(&["a", "b"]));
+    let mut new = HashMap::new();
+    new.insert(String::from("labels"), set(&["a", "b"]));
+    new.insert(String::from("owners"), set(&["a"]));
+    new.insert(String::from("tags"), set(&["b", "c"]));
+
+    let update = Update::from_changes(&serde_dynamodb::diff_items(&old, &new), &new, &[]);
+
+    assert_eq!(
+        update.update_expression.as_deref(),
+        Some("SET tags = :v2 ADD labels :v0 DELETE owners :v1")
+    );
+    assert!(update.attributes.names.is_empty());
+    assert_eq!(update.attributes.values[&String::from(":v0")], set(&["b"]));
+    assert_eq!(update.attributes.values[&String::from(":v1")], set(&["b"]));
+    assert_eq!(
+        update.attributes.values[&String::from(":v2")],
+        set(&["b", "c"])
+    );
+}
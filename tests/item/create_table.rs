@@ -0,0 +1,197 @@
+use rusoto_dynamodb::{
+    AttributeDefinition, GlobalSecondaryIndex, KeySchemaElement, LocalSecondaryIndex, Projection,
+    ProvisionedThroughput,
+};
+use serde::{Deserialize, Serialize};
+use serde_dynamodb_derive::DynamoItem;
+
+#[derive(Serialize, Deserialize, DynamoItem)]
+#[dynamodb(table = "orders", stream = "NEW_AND_OLD_IMAGES")]
+#[dynamodb(index = "by_status", partition_key = "status", sort_key = "total")]
+#[dynamodb(
+    index = "by_customer",
+    partition_key = "customer",
+    projection = "KEYS_ONLY"
+)]
+#[dynamodb(
+    local_index = "by_date",
+    sort_key = "created",
+    include = "status, total"
+)]
+struct Order {
+    #[dynamodb(partition_key)]
+    #[serde(rename = "pk")]
+    customer: String,
+    #[dynamodb(sort_key)]
+    id: u64,
+    status: String,
+    total: f64,
+    created: String,
+    #[dynamodb(ttl)]
+    expires: u64,
+}
+
+#[derive(Serialize, Deserialize, DynamoItem)]
+#[dynamodb(table = "blobs", read_capacity = 5, write_capacity = 2)]
+struct Blob {
+    #[dynamodb(partition_key)]
+    #[serde(with = "bytes")]
+    hash: Vec<u8>,
+}
+
+mod bytes {
+    struct Visitor;
+
+    impl serde::de::Visitor<'_> for Visitor {
+        type Value = Vec<u8>;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("bytes")
+        }
+
+        fn visit_bytes<E>(self, value: &[u8]) -> Result<Vec<u8>, E> {
+            Ok(value.to_vec())
+        }
+    }
+
+    pub fn serialize<S: serde::Serializer>(value: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(value)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_byte_buf(Visitor)
+    }
+}
+
+fn key(attribute_name: &str, key_type: &str) -> KeySchemaElement {
+    KeySchemaElement {
+        attribute_name: String::from(attribute_name),
+        key_type: String::from(key_type),
+    }
+}
+
+fn definition(attribute_name: &str, attribute_type: &str) -> AttributeDefinition {
+    AttributeDefinition {
+        attribute_name: String::from(attribute_name),
+        attribute_type: String::from(attribute_type),
+    }
+}
+
+#[test]
+fn can_describe_a_table_with_indexes() {
+    let input = serde_dynamodb::create_table_input::<Order>().unwrap();
+
+    assert_eq!(input.table_name, "orders");
+    assert_eq!(
+        input.key_schema,
+        vec![key("pk", "HASH"), key("id", "RANGE")]
+    );
+    assert_eq!(
+        input.attribute_definitions,
+        vec![
+            definition("pk", "S"),
+            definition("id", "N"),
+            definition("status", "S"),
+            definition("total", "N"),
+            definition("created", "S"),
+        ]
+    );
+    assert_eq!(
+        input.global_secondary_indexes,
+        Some(vec![
+            GlobalSecondaryIndex {
+                index_name: String::from("by_status"),
+                key_schema: vec![key("status", "HASH"), key("total", "RANGE")],
+                projection: Projection {
+                    projection_type: Some(String::from("ALL")),
+                    non_key_attributes: None,
+                },
+                provisioned_throughput: None,
+            },
+            GlobalSecondaryIndex {
+                index_name: String::from("by_customer"),
+                key_schema: vec![key("pk", "HASH")],
+                projection: Projection {
+                    projection_type: Some(String::from("KEYS_ONLY")),
+                    non_key_attributes: None,
+                },
+                provisioned_throughput: None,
+            },
+        ])
+    );
+    assert_eq!(
+        input.local_secondary_indexes,
+        Some(vec![LocalSecondaryIndex {
+            index_name: String::from("by_date"),
+            key_schema: vec![key("pk", "HASH"), key("created", "RANGE")],
+            projection: Projection {
+                projection_type: Some(String::from("INCLUDE")),
+                non_key_attributes: Some(vec![String::from("status"), String::from("total")]),
+            },
+        }])
+    );
+    assert_eq!(input.billing_mode.as_deref(), Some("PAY_PER_REQUEST"));
+    assert_eq!(input.provisioned_throughput, None);
+    let stream = input.stream_specification.unwrap();
+    assert!(stream.stream_enabled);
+    assert_eq!(
+        stream.stream_view_type.as_deref(),
+        Some("NEW_AND_OLD_IMAGES")
+    );
+
+    let ttl = serde_dynamodb::update_time_to_live_input::<Order>().unwrap();
+    assert_eq!(ttl.table_name, "orders");
+    assert_eq!(ttl.time_to_live_specification.attribute_name, "expires");
+    assert!(ttl.time_to_live_specification.enabled);
+}
+
+#[test]
+fn can_describe_a_provisioned_table() {
+    let input = serde_dynamodb::create_table_input::<Blob>().unwrap();
+
+    assert_eq!(input.attribute_definitions, vec![definition("hash", "B")]);
+    assert_eq!(input.billing_mode.as_deref(), Some("PROVISIONED"));
+    assert_eq!(
+        input.provisioned_throughput,
+        Some(ProvisionedThroughput {
+            read_capacity_units: 5,
+            write_capacity_units: 2,
+        })
+    );
+    assert_eq!(input.global_secondary_indexes, None);
+    assert_eq!(input.stream_specification, None);
+    assert!(serde_dynamodb::update_time_to_live_input::<Blob>().is_none());
+}
+
+#[test]
+fn fields_rejecting_default_values_are_traced() {
+    fn non_empty<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        if value.is_empty() {
+            return Err(serde::de::Error::custom("empty string"));
+        }
+        Ok(value)
+    }
+
+    #[derive(Serialize, Deserialize, DynamoItem)]
+    #[dynamodb(table = "events")]
+    struct Event {
+        #[serde(deserialize_with = "non_empty")]
+        created: String,
+        #[dynamodb(partition_key)]
+        id: String,
+        #[dynamodb(sort_key)]
+        #[serde(deserialize_with = "non_empty")]
+        kind: String,
+        sequence: u64,
+    }
+
+    let input = serde_dynamodb::create_table_input::<Event>().unwrap();
+    assert_eq!(
+        input.attribute_definitions,
+        vec![definition("id", "S"), definition("kind", "S")]
+    );
+}
@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+
+use rusoto_dynamodb::AttributeValue;
+use serde::{Deserialize, Serialize};
+use serde_dynamodb::path::AttributePath;
+use serde_dynamodb::Change;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+struct Address {
+    city: String,
+    lines: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+struct Person {
+    id: String,
+    age: u8,
+    nickname: Option<String>,
+    address: Address,
+}
+
+fn person() -> Person {
+    Person {
+        id: String::from("1"),
+        age: 30,
+        nickname: None,
+        address: Address {
+            city: String::from("London"),
+            lines: vec![String::from("10 Downing Street")],
+        },
+    }
+}
+
+fn s(value: &str) -> AttributeValue {
+    AttributeValue {
+        s: Some(String::from(value)),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn same_items_have_no_changes() {
+    let item = serde_dynamodb::to_hashmap(&person()).unwrap();
+    assert!(serde_dynamodb::diff_items(&item, &item).is_empty());
+}
+
+#[test]
+fn can_diff_nested_attributes() {
+    let old = person();
+    let mut new = person();
+    new.age = 31;
+    new.address.city = String::from("Paris");
+    new.address.lines.push(String::from("Flat 2"));
+
+    let changes = serde_dynamodb::diff_items(
+        &serde_dynamodb::to_hashmap(&old).unwrap(),
+        &serde_dynamodb::to_hashmap(&new).unwrap(),
+    );
+
+    assert_eq!(
+        changes,
+        vec![
+            Change::Changed {
+                path: AttributePath::attribute("address").with_attribute("city"),
+                old: s("London"),
+                new: s("Paris"),
+            },
+            Change::Added {
+                path: AttributePath::attribute("address")
+                    .with_attribute("lines")
+                    .with_index(1),
+                value: s("Flat 2"),
+            },
+            Change::Changed {
+                path: AttributePath::attribute("age"),
+                old: AttributeValue {
+                    n: Some(String::from("30")),
+                    ..Default::default()
+                },
+                new: AttributeValue {
+                    n: Some(String::from("31")),
+                    ..Default::default()
+                },
+            },
+        ]
+    );
+    assert_eq!(changes[1].path().to_string(), "address.lines[1]");
+}
+
+#[test]
+fn can_diff_added_and_removed_attributes() {
+    let mut old = HashMap::new();
+    old.insert(String::from("a"), s("a"));
+    let mut new = HashMap::new();
+    new.insert(String::from("b"), s("b"));
+
+    assert_eq!(
+        serde_dynamodb::diff_items(&old, &new),
+        vec![
+            Change::Removed {
+                path: AttributePath::attribute("a"),
+                value: s("a"),
+            },
+            Change::Added {
+                path: AttributePath::attribute("b"),
+                value: s("b"),
+            },
+        ]
+    );
+}
+
+#[test]
+fn can_diff_set_members() {
+    let set = |members: &[&str]| AttributeValue {
+        ss: Some(members.iter().map(|member| String::from(*member)).collect()),
+        ..Default::default()
+    };
+    let mut old = HashMap::new();
+    old.insert(String::from("tags"), set(&["a", "b"]));
+    let mut new = HashMap::new();
+    new.insert(String::from("tags"), set(&["b", "c"]));
+
+    assert_eq!(
+        serde_dynamodb::diff_items(&old, &new),
+        vec![
+            Change::MembersAdded {
+                path: AttributePath::attribute("tags"),
+                members: set(&["c"]),
+            },
+            Change::MembersRemoved {
+                path: AttributePath::attribute("tags"),
+                members: set(&["a"]),
+            },
+        ]
+    );
+}
+
+#[test]
+fn numbers_are_compared_by_value() {
+    let n = |value: &str| AttributeValue {
+        n: Some(String::from(value)),
+        ..Default::default()
+    };
+    let ns = |members: &[&str]| AttributeValue {
+        ns: Some(members.iter().map(|member| String::from(*member)).collect()),
+        ..Default::default()
+    };
+    let mut old = HashMap::new();
+    old.insert(String::from("price"), n("1.50"));
+    old.insert(String::from("total"), n("1000"));
+    old.insert(String::from("sizes"), ns(&["1", "2.0"]));
+    let mut new = HashMap::new();
+    new.insert(String::from("price"), n("1.5"));
+    new.insert(String::from("total"), n("1E+3"));
+    new.insert(String::from("sizes"), ns(&["2", "1.00"]));
+    assert!(serde_dynamodb::diff_items(&old, &new).is_empty());
+
+    new.insert(String::from("price"), n("1.05"));
+    assert_eq!(
+        serde_dynamodb::diff_items(&old, &new),
+        vec![Change::Changed {
+            path: AttributePath::attribute("price"),
+            old: n("1.50"),
+            new: n("1.05"),
+        }]
+    );
+}
+
+#[test]
+fn numbers_with_huge_exponents_are_compared_as_written() {
+    let n = |value: &str| AttributeValue {
+        n: Some(String::from(value)),
+        ..Default::default()
+    };
+    let mut old = HashMap::new();
+    old.insert(String::from("price"), n("1e9223372036854775807"));
+    let mut new = HashMap::new();
+    new.insert(String::from("price"), n("1e9223372036854775807"));
+    assert!(serde_dynamodb::diff_items(&old, &new).is_empty());
+
+    new.insert(String::from("price"), n("10e9223372036854775807"));
+    assert_eq!(
+        serde_dynamodb::diff_items(&old, &new),
+        vec![Change::Changed {
+            path: AttributePath::attribute("price"),
+            old: n("1e9223372036854775807"),
+            new: n("10e9223372036854775807"),
+        }]
+    );
+}
+
+#[test]
+fn typed_diff_ignores_unknown_attributes() {
+    let mut old = serde_dynamodb::to_hashmap(&person()).unwrap();
+    old.insert(String::from("unknown"), s("a"));
+    let mut new_person = person();
+    new_person.nickname = Some(String::from("Bob"));
+    let new = serde_dynamodb::to_hashmap(&new_person).unwrap();
+
+    let changes = serde_dynamodb::diff::<Person, _>(old, new).unwrap();
+    assert_eq!(
+        changes,
+        vec![Change::Changed {
+            path: AttributePath::attribute("nickname"),
+            old: AttributeValue {
+                null: Some(true),
+                ..Default::default()
+            },
+            new: s("Bob"),
+        }]
+    );
+}
@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+
+use rusoto_dynamodb::AttributeValue;
+use serde::{Deserialize, Serialize};
+use serde_dynamodb::DynamoItem;
+use serde_dynamodb_derive::DynamoItem;
+
+#[derive(Serialize, Deserialize, DynamoItem)]
+#[dynamodb(table = "tasks")]
+struct Task {
+    #[dynamodb(partition_key)]
+    #[serde(rename = "project")]
+    project_id: String,
+    #[dynamodb(sort_key)]
+    id: u32,
+    title: String,
+}
+
+#[derive(Serialize, Deserialize, DynamoItem)]
+#[dynamodb(table = "projects")]
+struct Project {
+    #[dynamodb(partition_key)]
+    id: String,
+    name: String,
+}
+
+fn task() -> Task {
+    Task {
+        project_id: String::from("p"),
+        id: 7,
+        title: String::from("write tests"),
+    }
+}
+
+#[test]
+fn can_describe_the_key_schema() {
+    assert_eq!(Task::TABLE_NAME, "tasks");
+    assert_eq!(Task::PARTITION_KEY, "project");
+    assert_eq!(Task::SORT_KEY, Some("id"));
+    assert_eq!(Project::TABLE_NAME, "projects");
+    assert_eq!(Project::SORT_KEY, None);
+}
+
+#[test]
+fn can_extract_the_key() {
+    let mut expected = HashMap::new();
+    expected.insert(
+        String::from("project"),
+        AttributeValue {
+            s: Some(String::from("p")),
+            ..Default::default()
+        },
+    );
+    expected.insert(
+        String::from("id"),
+        AttributeValue {
+            n: Some(String::from("7")),
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(task().try_key().unwrap(), expected);
+}
+
+#[test]
+fn typed_key_serializes_to_the_key() {
+    let task = task();
+    let key = TaskKey::from(&task);
+
+    assert_eq!(
+        key,
+        TaskKey {
+            project_id: String::from("p"),
+            id: 7,
+        }
+    );
+    assert_eq!(
+        serde_dynamodb::to_hashmap(&key).unwrap(),
+        task.try_key().unwrap()
+    );
+}
+
+#[test]
+fn the_key_follows_serde_with() {
+    use std::time::{Duration, SystemTime};
+
+    #[derive(Serialize, Deserialize, DynamoItem)]
+    #[dynamodb(table = "days")]
+    struct Day {
+        #[dynamodb(partition_key)]
+        #[serde(with = "serde_dynamodb::timestamp::epoch_seconds")]
+        at: SystemTime,
+        note: String,
+    }
+
+    let day = Day {
+        at: SystemTime::UNIX_EPOCH + Duration::from_secs(86400),
+        note: String::from("first"),
+    };
+    let item = serde_dynamodb::to_hashmap(&day).unwrap();
+    assert_eq!(item["at"].n.as_deref(), Some("86400"));
+    assert_eq!(day.try_key().unwrap()["at"], item["at"]);
+
+    let key = serde_dynamodb::to_hashmap(&DayKey::from(&day)).unwrap();
+    assert_eq!(key, day.try_key().unwrap());
+    let key: DayKey = serde_dynamodb::from_hashmap(key).unwrap();
+    assert_eq!(key.at, day.at);
+}
+
+#[test]
+fn the_key_may_fail_to_serialize() {
+    fn refuse<S: serde::Serializer>(_: &String, _: S) -> Result<S::Ok, S::Error> {
+        Err(serde::ser::Error::custom("refused"))
+    }
+
+    #[derive(Serialize, DynamoItem)]
+    #[dynamodb(table = "notes")]
+    struct Note {
+        #[dynamodb(partition_key)]
+        #[serde(serialize_with = "refuse")]
+        id: String,
+    }
+
+    let note = Note {
+        id: String::from("1"),
+    };
+    assert_eq!(note.try_key().unwrap_err().message, "refused");
+}
@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+
+use rusoto_dynamodb::AttributeValue;
+use serde::{Deserialize, Serialize};
+use serde_dynamodb::{DynamoItem, IndexProjection};
+use serde_dynamodb_derive::DynamoItem;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, DynamoItem)]
+#[dynamodb(table = "orders")]
+#[dynamodb(index = "by_status", partition_key = "status", sort_key = "total")]
+#[dynamodb(
+    index = "by-customer",
+    partition_key = "customer",
+    projection = "KEYS_ONLY"
+)]
+#[dynamodb(local_index = "by_date", sort_key = "created", include = "note")]
+struct Order {
+    #[dynamodb(partition_key)]
+    #[serde(rename = "pk")]
+    region: String,
+    #[dynamodb(sort_key)]
+    id: u64,
+    status: String,
+    total: u64,
+    customer: String,
+    created: String,
+    note: Option<String>,
+}
+
+fn order() -> Order {
+    Order {
+        region: String::from("eu"),
+        id: 3,
+        status: String::from("shipped"),
+        total: 42,
+        customer: String::from("c1"),
+        created: String::from("2020-01-01"),
+        note: None,
+    }
+}
+
+fn only(item: HashMap<String, AttributeValue>, names: &[&str]) -> HashMap<String, AttributeValue> {
+    item.into_iter()
+        .filter(|(name, _)| names.contains(&name.as_str()))
+        .collect()
+}
+
+#[test]
+fn index_key_has_the_keys_of_the_index_and_of_the_table() {
+    let order = order();
+    let key = OrderByStatusKey::from(&order);
+    assert_eq!(
+        key,
+        OrderByStatusKey {
+            status: String::from("shipped"),
+            total: 42,
+            region: String::from("eu"),
+            id: 3,
+        }
+    );
+
+    let item = serde_dynamodb::to_hashmap(&order).unwrap();
+    assert_eq!(
+        serde_dynamodb::to_hashmap(&OrderByCustomerKey::from(&order)).unwrap(),
+        only(item, &["customer", "pk", "id"])
+    );
+    assert_eq!(Order::INDEXES[1].projection, IndexProjection::KeysOnly);
+}
+
+#[test]
+fn can_read_an_item_from_an_index_projecting_all_attributes() {
+    let item = serde_dynamodb::to_hashmap(&order()).unwrap();
+    let read: Order = serde_dynamodb::from_index_item("by_status", item).unwrap();
+    assert_eq!(read, order());
+}
+
+#[test]
+fn attributes_not_projected_are_an_error() {
+    let item = only(
+        serde_dynamodb::to_hashmap(&order()).unwrap(),
+        &["customer", "pk", "id"],
+    );
+    let error = serde_dynamodb::from_index_item::<Order, _>("by-customer", item).unwrap_err();
+    assert_eq!(
+        error.message,
+        "attribute status is not projected in index by-customer of table orders"
+    );
+
+    let error = serde_dynamodb::from_index_item::<Order, _>("by_date", HashMap::new()).unwrap_err();
+    assert!(error.message.contains("not projected in index by_date"));
+
+    let error = serde_dynamodb::from_index_item::<Order, _>("by_name", HashMap::new()).unwrap_err();
+    assert_eq!(error.message, "table orders has no index by_name");
+}
+
+#[test]
+fn attributes_not_projected_can_be_present() {
+    let item = serde_dynamodb::to_hashmap(&order()).unwrap();
+    let read: Order = serde_dynamodb::from_index_item("by-customer", item).unwrap();
+    assert_eq!(read, order());
+}
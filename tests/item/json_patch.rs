@@ -0,0 +1,126 @@
+use std::collections::{BTreeSet, HashMap};
+
+use serde::Serialize;
+use serde_dynamodb::PatchOperation;
+use serde_json::json;
+
+#[derive(Serialize, Clone)]
+struct Document {
+    title: String,
+    score: f64,
+    #[serde(rename = "a/b")]
+    slashed: Option<u32>,
+    lines: Vec<String>,
+    meta: HashMap<String, i32>,
+    tags: BTreeSet<String>,
+}
+
+fn document() -> Document {
+    let mut meta = HashMap::new();
+    meta.insert(String::from("views"), 1);
+    Document {
+        title: String::from("draft"),
+        score: 1.5,
+        slashed: None,
+        lines: vec![
+            String::from("a"),
+            String::from("b"),
+            String::from("c"),
+            String::from("d"),
+        ],
+        meta,
+        tags: BTreeSet::new(),
+    }
+}
+
+#[test]
+fn can_convert_attribute_values_to_json() {
+    let item = serde_dynamodb::to_hashmap(&document()).unwrap();
+    let json: serde_json::Map<String, serde_json::Value> = item
+        .iter()
+        .map(|(name, value)| (name.clone(), serde_dynamodb::to_json_value(value).unwrap()))
+        .collect();
+
+    assert_eq!(
+        serde_json::Value::Object(json),
+        json!({
+            "title": "draft",
+            "score": 1.5,
+            "a/b": null,
+            "lines": ["a", "b", "c", "d"],
+            "meta": {"views": 1},
+            "tags": [],
+        })
+    );
+}
+
+#[test]
+fn converts_numbers_exactly() {
+    let n = |value: &str| rusoto_dynamodb::AttributeValue {
+        n: Some(String::from(value)),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        serde_dynamodb::to_json_value(&n("18446744073709551615")).unwrap(),
+        json!(u64::MAX)
+    );
+    assert_eq!(
+        serde_dynamodb::to_json_value(&n("-9223372036854775808")).unwrap(),
+        json!(i64::MIN)
+    );
+    assert_eq!(
+        serde_dynamodb::to_json_value(&n("0.10")).unwrap(),
+        json!(0.1)
+    );
+    assert!(serde_dynamodb::to_json_value(&n("18446744073709551617")).is_err());
+    assert!(serde_dynamodb::to_json_value(&n("0.1000000000000000001")).is_err());
+}
+
+#[test]
+fn can_generate_json_patch() {
+    let old = document();
+    let mut new = document();
+    new.title = String::from("published");
+    new.slashed = Some(3);
+    new.lines.truncate(1);
+    new.meta.insert(String::from("likes"), 2);
+
+    let patch = serde_dynamodb::json_patch(
+        &serde_dynamodb::to_hashmap(&old).unwrap(),
+        &serde_dynamodb::to_hashmap(&new).unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        serde_json::to_value(&patch).unwrap(),
+        json!([
+            {"op": "replace", "path": "/a~1b", "value": 3},
+            {"op": "remove", "path": "/lines/3"},
+            {"op": "remove", "path": "/lines/2"},
+            {"op": "remove", "path": "/lines/1"},
+            {"op": "add", "path": "/meta/likes", "value": 2},
+            {"op": "replace", "path": "/title", "value": "published"},
+        ])
+    );
+}
+
+#[test]
+fn replaces_sets_with_changed_members() {
+    let set = |members: &[&str]| rusoto_dynamodb::AttributeValue {
+        ss: Some(members.iter().map(|member| String::from(*member)).collect()),
+        ..Default::default()
+    };
+    let mut old = HashMap::new();
+    old.insert(String::from("tags"), set(&["a", "b"]));
+    let mut new = HashMap::new();
+    new.insert(String::from("tags"), set(&["b", "c"]));
+
+    assert_eq!(
+        serde_dynamodb::json_patch(&old, &new).unwrap(),
+        vec![PatchOperation::Replace {
+            path: String::from("/tags"),
+            value: json!(["b", "c"]),
+        }]
+    );
+}
@@ -0,0 +1,13 @@
+//! The description of items and tables, and how their attributes are written.
+
+#![cfg(feature = "rusoto_dynamodb")]
+
+mod create_table;
+mod diff;
+mod dynamo_item;
+mod index_view;
+#[cfg(feature = "json-patch")]
+mod json_patch;
+#[cfg(any(feature = "rust_decimal", feature = "bigdecimal"))]
+mod number;
+mod timestamp;
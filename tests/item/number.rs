@@ -0,0 +1,142 @@
+#[cfg(feature = "rust_decimal")]
+use std::collections::HashMap;
+
+#[cfg(feature = "rust_decimal")]
+use rusoto_dynamodb::AttributeValue;
+use serde::{Deserialize, Serialize};
+use serde_dynamodb::{from_hashmap, to_hashmap};
+
+#[cfg(feature = "rust_decimal")]
+#[test]
+fn can_store_decimals_exactly() {
+    use std::str::FromStr;
+
+    use rust_decimal::Decimal;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Invoice {
+        #[serde(with = "serde_dynamodb::number")]
+        total: Decimal,
+        #[serde(default, with = "serde_dynamodb::number::option")]
+        discount: Option<Decimal>,
+        #[serde(default, with = "serde_dynamodb::number::option")]
+        tax: Option<Decimal>,
+    }
+
+    let invoice = Invoice {
+        total: Decimal::from_str("79228162514264337593543950.335").unwrap(),
+        discount: Some(Decimal::from_str("-0.10").unwrap()),
+        tax: None,
+    };
+    let item = to_hashmap(&invoice).unwrap();
+    assert_eq!(
+        item["total"].n.as_deref(),
+        Some("79228162514264337593543950.335")
+    );
+    assert_eq!(item["discount"].n.as_deref(), Some("-0.10"));
+    assert_eq!(from_hashmap::<Invoice, _>(item).unwrap(), invoice);
+
+    let mut item = HashMap::new();
+    item.insert(
+        String::from("total"),
+        AttributeValue {
+            n: Some(String::from("1.5E+3")),
+            ..Default::default()
+        },
+    );
+    let read: Invoice = from_hashmap(item).unwrap();
+    assert_eq!(read.total, Decimal::from(1500));
+    assert_eq!(read.discount, None);
+}
+
+#[cfg(feature = "bigdecimal")]
+#[test]
+fn can_store_big_decimals_up_to_38_digits() {
+    use std::str::FromStr;
+
+    use bigdecimal::BigDecimal;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Balance {
+        #[serde(with = "serde_dynamodb::number")]
+        amount: BigDecimal,
+        amounts: Vec<Amount>,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Amount(#[serde(with = "serde_dynamodb::number")] BigDecimal);
+
+    let digits = "1234567890123456789012345678.9012345678";
+    let balance = Balance {
+        amount: BigDecimal::from_str(digits).unwrap(),
+        amounts: vec![Amount(BigDecimal::from_str("0.1").unwrap())],
+    };
+    let item = to_hashmap(&balance).unwrap();
+    assert_eq!(item["amount"].n.as_deref(), Some(digits));
+    assert_eq!(
+        item["amounts"].l.as_ref().unwrap()[0].n.as_deref(),
+        Some("0.1")
+    );
+    assert_eq!(from_hashmap::<Balance, _>(item).unwrap(), balance);
+
+    let too_precise = Balance {
+        amount: BigDecimal::from_str("1234567890123456789012345678.90123456789").unwrap(),
+        amounts: vec![],
+    };
+    assert!(to_hashmap(&too_precise).is_err());
+    let too_large = Balance {
+        amount: BigDecimal::from_str("1e126").unwrap(),
+        amounts: vec![],
+    };
+    assert!(to_hashmap(&too_large).is_err());
+    let trailing_zeros = Balance {
+        amount: BigDecimal::from_str("1e125").unwrap(),
+        amounts: vec![],
+    };
+    assert!(to_hashmap(&trailing_zeros).is_ok());
+}
+
+#[cfg(feature = "rust_decimal")]
+#[test]
+fn a_number_can_not_be_read_from_a_string() {
+    #[derive(Debug, Deserialize)]
+    struct Invoice {
+        #[serde(with = "serde_dynamodb::number")]
+        #[allow(dead_code)]
+        total: rust_decimal::Decimal,
+    }
+
+    let mut item = HashMap::new();
+    item.insert(
+        String::from("total"),
+        AttributeValue {
+            s: Some(String::from("1.5")),
+            ..Default::default()
+        },
+    );
+    assert!(from_hashmap::<Invoice, _>(item).is_err());
+}
+
+#[cfg(feature = "rust_decimal")]
+#[test]
+fn exact_numbers_are_numbers_in_keys() {
+    use serde_dynamodb_derive::DynamoItem;
+
+    #[derive(Serialize, Deserialize, DynamoItem)]
+    #[dynamodb(table = "prices")]
+    struct Price {
+        #[dynamodb(partition_key)]
+        product: String,
+        #[dynamodb(sort_key)]
+        #[serde(with = "serde_dynamodb::number")]
+        amount: rust_decimal::Decimal,
+    }
+
+    let input = serde_dynamodb::create_table_input::<Price>().unwrap();
+    let amount = input
+        .attribute_definitions
+        .iter()
+        .find(|definition| definition.attribute_name == "amount")
+        .unwrap();
+    assert_eq!(amount.attribute_type, "N");
+}
@@ -0,0 +1,165 @@
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use serde_dynamodb::{from_hashmap, to_hashmap};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Event {
+    #[serde(with = "serde_dynamodb::timestamp::epoch_millis")]
+    at: SystemTime,
+    #[serde(with = "serde_dynamodb::timestamp::epoch_seconds::option")]
+    before_epoch: Option<SystemTime>,
+}
+
+#[test]
+fn can_store_system_times_as_numbers() {
+    let event = Event {
+        at: SystemTime::UNIX_EPOCH + Duration::from_millis(1_600_000_000_123),
+        before_epoch: Some(SystemTime::UNIX_EPOCH - Duration::from_secs(60)),
+    };
+
+    let item = to_hashmap(&event).unwrap();
+    assert_eq!(item["at"].n.as_deref(), Some("1600000000123"));
+    assert_eq!(item["before_epoch"].n.as_deref(), Some("-60"));
+    assert_eq!(from_hashmap::<Event, _>(item).unwrap(), event);
+}
+
+#[test]
+fn times_before_the_epoch_are_rounded_down() {
+    use serde_dynamodb::timestamp::{EpochMillis, EpochSeconds};
+
+    let time = SystemTime::UNIX_EPOCH - Duration::from_micros(1_500);
+    assert_eq!(time.to_epoch_millis(), Some(-2));
+    assert_eq!(time.to_epoch_seconds(), Some(-1));
+
+    #[cfg(feature = "chrono")]
+    {
+        let time = chrono::DateTime::from_timestamp_nanos(-1_500_000);
+        assert_eq!(time.to_epoch_millis(), Some(-2));
+        assert_eq!(time.to_epoch_seconds(), Some(-1));
+    }
+
+    #[cfg(feature = "time")]
+    {
+        let time = time::OffsetDateTime::from_unix_timestamp_nanos(-1_500_000).unwrap();
+        assert_eq!(time.to_epoch_millis(), Some(-2));
+        assert_eq!(time.to_epoch_seconds(), Some(-1));
+    }
+}
+
+#[test]
+fn durations_past_the_last_time_are_errors() {
+    #[derive(Serialize)]
+    struct Session {
+        #[serde(with = "serde_dynamodb::timestamp::epoch_seconds")]
+        expires: Duration,
+    }
+
+    let session = Session {
+        expires: Duration::MAX,
+    };
+    assert_eq!(
+        to_hashmap(&session).unwrap_err().message,
+        "time out of range"
+    );
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn can_store_chrono_times() {
+    use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Order {
+        #[serde(with = "serde_dynamodb::timestamp::iso8601")]
+        created: DateTime<Utc>,
+        #[serde(with = "serde_dynamodb::timestamp::iso8601::option")]
+        shipped: Option<DateTime<Utc>>,
+        #[serde(with = "serde_dynamodb::timestamp::iso8601")]
+        day: NaiveDate,
+        #[serde(with = "serde_dynamodb::timestamp::epoch_seconds")]
+        due: NaiveDate,
+        #[serde(with = "serde_dynamodb::timestamp::epoch_millis")]
+        updated: DateTime<Utc>,
+    }
+
+    let created = Utc.with_ymd_and_hms(2020, 9, 13, 12, 26, 40).unwrap();
+    let order = Order {
+        created,
+        shipped: None,
+        day: NaiveDate::from_ymd_opt(2020, 9, 13).unwrap(),
+        due: NaiveDate::from_ymd_opt(1970, 1, 2).unwrap(),
+        updated: created + chrono::Duration::milliseconds(5),
+    };
+
+    let item = to_hashmap(&order).unwrap();
+    assert_eq!(
+        item["created"].s.as_deref(),
+        Some("2020-09-13T12:26:40.000Z")
+    );
+    assert_eq!(item["day"].s.as_deref(), Some("2020-09-13"));
+    assert_eq!(item["due"].n.as_deref(), Some("86400"));
+    assert_eq!(item["updated"].n.as_deref(), Some("1600000000005"));
+    assert_eq!(from_hashmap::<Order, _>(item).unwrap(), order);
+
+    let mut item = to_hashmap(&order).unwrap();
+    item.get_mut("created").unwrap().s = Some(String::from("2020-09-13T14:26:40+02:00"));
+    assert_eq!(from_hashmap::<Order, _>(item).unwrap().created, created);
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn can_store_time_offset_date_times() {
+    use time::{Duration, OffsetDateTime, UtcOffset};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Order {
+        #[serde(with = "serde_dynamodb::timestamp::iso8601")]
+        created: OffsetDateTime,
+        #[serde(with = "serde_dynamodb::timestamp::epoch_seconds")]
+        due: OffsetDateTime,
+    }
+
+    let created =
+        OffsetDateTime::from_unix_timestamp(1_600_000_000).unwrap() + Duration::milliseconds(7);
+    let order = Order {
+        created: created.to_offset(UtcOffset::from_hms(2, 0, 0).unwrap()),
+        due: OffsetDateTime::from_unix_timestamp(86400).unwrap(),
+    };
+
+    let item = to_hashmap(&order).unwrap();
+    assert_eq!(
+        item["created"].s.as_deref(),
+        Some("2020-09-13T12:26:40.007Z")
+    );
+    assert_eq!(item["due"].n.as_deref(), Some("86400"));
+    let read: Order = from_hashmap(item).unwrap();
+    assert_eq!(read.created, created);
+    assert_eq!(read.due, order.due);
+}
+
+#[cfg(all(feature = "chrono", feature = "time"))]
+#[test]
+fn iso8601_strings_sort_in_time_order() {
+    use serde_dynamodb::timestamp::Iso8601;
+
+    let times: Vec<String> = [0, 999, 1_000, 59_999, 1_600_000_000_000]
+        .iter()
+        .map(|millis| {
+            chrono::DateTime::from_timestamp_millis(*millis)
+                .unwrap()
+                .to_iso8601()
+        })
+        .collect();
+    let mut sorted = times.clone();
+    sorted.sort();
+    assert_eq!(sorted, times);
+
+    let from_time = time::OffsetDateTime::from_unix_timestamp(1_600_000_000).unwrap();
+    assert_eq!(
+        from_time.to_iso8601(),
+        chrono::DateTime::from_timestamp(1_600_000_000, 0)
+            .unwrap()
+            .to_iso8601()
+    );
+}
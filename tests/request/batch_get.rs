@@ -0,0 +1,136 @@
+use std::time::Duration;
+
+use futures::executor::block_on;
+use serde::Deserialize;
+use serde_dynamodb::{batch_get, batch_get_with_retry_policy, RetryPolicy};
+use serde_json::json;
+
+use crate::mock::Mock;
+use crate::task::{item, task, Task, TaskKey};
+
+#[test]
+fn can_match_items_to_their_keys() {
+    let mock = Mock::default();
+    mock.respond(json!({"Responses": {"tasks": [item(3), item(1)]}}));
+    let client = mock.client();
+
+    let keys = vec![
+        TaskKey { id: 1 },
+        TaskKey { id: 2 },
+        TaskKey { id: 3 },
+        TaskKey { id: 1 },
+    ];
+    let output = block_on(batch_get::<TaskKey, Task>(
+        &client,
+        String::from("tasks"),
+        keys,
+    ))
+    .unwrap();
+
+    assert_eq!(output.items[&TaskKey { id: 3 }], task(3));
+    assert_eq!(
+        output.into_ordered(),
+        vec![
+            (TaskKey { id: 1 }, Some(task(1))),
+            (TaskKey { id: 2 }, None),
+            (TaskKey { id: 3 }, Some(task(3))),
+        ]
+    );
+    let requests = mock.requests();
+    assert_eq!(requests[0].operation, "BatchGetItem");
+    assert_eq!(
+        requests[0].body["RequestItems"]["tasks"]["Keys"],
+        json!([{"id": {"N": "1"}}, {"id": {"N": "2"}}, {"id": {"N": "3"}}])
+    );
+}
+
+#[test]
+fn can_split_keys_in_batches_of_100() {
+    let mock = Mock::new(|_| (200, json!({"Responses": {"tasks": []}})));
+    let client = mock.client();
+
+    let keys = (0..250).map(|id| TaskKey { id });
+    let output = block_on(batch_get::<TaskKey, Task>(
+        &client,
+        String::from("tasks"),
+        keys,
+    ))
+    .unwrap();
+
+    assert_eq!(output.keys.len(), 250);
+    let sizes: Vec<usize> = mock
+        .requests()
+        .iter()
+        .map(|request| {
+            request.body["RequestItems"]["tasks"]["Keys"]
+                .as_array()
+                .unwrap()
+                .len()
+        })
+        .collect();
+    assert_eq!(sizes, vec![100, 100, 50]);
+}
+
+#[test]
+fn can_retry_unprocessed_keys() {
+    let mock = Mock::default();
+    mock.respond(json!({
+        "Responses": {"tasks": [item(1)]},
+        "UnprocessedKeys": {"tasks": {"Keys": [{"id": {"N": "2"}}]}},
+    }))
+    .respond(json!({"Responses": {"tasks": [item(2)]}}));
+    let client = mock.client();
+    let policy = RetryPolicy {
+        max_retries: 1,
+        base_delay: Duration::from_secs(0),
+        max_delay: Duration::from_secs(0),
+    };
+
+    let keys = vec![TaskKey { id: 1 }, TaskKey { id: 2 }];
+    let output = block_on(batch_get_with_retry_policy::<TaskKey, Task>(
+        &client,
+        String::from("tasks"),
+        keys,
+        policy,
+    ))
+    .unwrap();
+
+    assert_eq!(output.retries, 1);
+    assert!(output.unprocessed_keys.is_empty());
+    assert_eq!(output.items.len(), 2);
+    assert_eq!(
+        mock.requests()[1].body["RequestItems"]["tasks"]["Keys"],
+        json!([{"id": {"N": "2"}}])
+    );
+}
+
+#[test]
+fn numbers_in_keys_are_matched_by_value() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Title {
+        title: String,
+    }
+
+    let mock = Mock::default();
+    mock.respond(json!({
+        "Responses": {"tasks": [{"id": {"N": "1.50E1"}, "title": {"S": "task 15"}}]},
+    }));
+    let client = mock.client();
+
+    let output = block_on(batch_get::<TaskKey, Title>(
+        &client,
+        String::from("tasks"),
+        vec![TaskKey { id: 15 }],
+    ))
+    .unwrap();
+
+    assert_eq!(
+        output.into_ordered(),
+        vec![(
+            TaskKey { id: 15 },
+            Some(Title {
+                title: String::from("task 15")
+            })
+        )]
+    );
+}
@@ -0,0 +1,202 @@
+use std::time::Duration;
+
+use futures::executor::block_on;
+use rusoto_dynamodb::BatchWriteItemError;
+use serde_dynamodb::{BatchWrite, RequestError, RetryPolicy};
+use serde_json::json;
+
+use crate::mock::Mock;
+use crate::task::{task, Task, TaskKey};
+
+fn no_delay(max_retries: u32) -> RetryPolicy {
+    RetryPolicy {
+        max_retries,
+        base_delay: Duration::from_secs(0),
+        max_delay: Duration::from_secs(0),
+    }
+}
+
+#[test]
+fn can_split_in_batches_of_25() {
+    let batches = BatchWrite::new(String::from("tasks"), &["id"])
+        .put_all((0..60).map(task))
+        .batches()
+        .unwrap();
+
+    let sizes: Vec<usize> = batches.iter().map(Vec::len).collect();
+    assert_eq!(sizes, vec![25, 25, 10]);
+}
+
+#[test]
+fn only_the_last_request_on_a_key_is_kept_in_a_batch() {
+    let batches = BatchWrite::new(String::from("tasks"), &["id"])
+        .put(&task(1))
+        .put(&task(2))
+        .delete(&TaskKey { id: 1 })
+        .batches()
+        .unwrap();
+
+    assert_eq!(batches.len(), 1);
+    assert_eq!(batches[0].len(), 2);
+    assert!(batches[0][0].delete_request.is_some());
+    assert!(batches[0][1].put_request.is_some());
+}
+
+#[cfg(feature = "rust_decimal")]
+#[test]
+fn keys_are_compared_by_number_value() {
+    use std::str::FromStr;
+
+    use rust_decimal::Decimal;
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    struct Key {
+        #[serde(with = "serde_dynamodb::number")]
+        id: Decimal,
+    }
+
+    let batches = BatchWrite::new(String::from("tasks"), &["id"])
+        .put(&task(1))
+        .delete(&Key {
+            id: Decimal::from_str("1.0").unwrap(),
+        })
+        .batches()
+        .unwrap();
+
+    assert_eq!(batches.len(), 1);
+    assert_eq!(batches[0].len(), 1);
+    assert!(batches[0][0].delete_request.is_some());
+}
+
+#[test]
+fn a_replaced_request_must_fit_in_its_batch() {
+    let large = |id, len| Task {
+        id,
+        title: "x".repeat(len),
+    };
+    let batches = BatchWrite::new(String::from("tasks"), &["id"])
+        .put(&task(1))
+        .put_all((2..=24).map(|id| large(id, 600_000)))
+        .put(&large(1, 3_000_000))
+        .batches()
+        .unwrap();
+
+    let sizes: Vec<usize> = batches.iter().map(Vec::len).collect();
+    assert_eq!(sizes, vec![23, 1]);
+    assert_eq!(
+        batches[1][0].put_request.as_ref().unwrap().item["title"]
+            .s
+            .as_ref()
+            .map(String::len),
+        Some(3_000_000)
+    );
+}
+
+#[test]
+fn key_attributes_must_be_present() {
+    let wrong_key = BatchWrite::new(String::from("tasks"), &["task_id"])
+        .put(&task(1))
+        .put(&task(2));
+    assert!(wrong_key.batches().is_err());
+
+    let missing_sort_key =
+        BatchWrite::new(String::from("tasks"), &["id", "created"]).delete(&TaskKey { id: 1 });
+    assert!(missing_sort_key.batches().is_err());
+}
+
+#[test]
+fn can_retry_unprocessed_items() {
+    let mock = Mock::default();
+    mock.respond(json!({"UnprocessedItems": {"tasks": [{"PutRequest": {"Item": {"id": {"N": "2"}}}}]}}))
+        .respond_with(
+            400,
+            json!({"__type": "com.amazonaws.dynamodb.v20120810#ProvisionedThroughputExceededException", "message": "slow down"}),
+        )
+        .respond(json!({}));
+    let client = mock.client();
+
+    let output = block_on(
+        BatchWrite::new(String::from("tasks"), &["id"])
+            .put_all((1..=3).map(task))
+            .retry_policy(no_delay(5))
+            .send(&client),
+    )
+    .unwrap();
+
+    assert!(output.unprocessed_items.is_empty());
+    assert_eq!(output.retries, 2);
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests[0].operation, "BatchWriteItem");
+    assert_eq!(
+        requests[0].body["RequestItems"]["tasks"]
+            .as_array()
+            .unwrap()
+            .len(),
+        3
+    );
+    assert_eq!(
+        requests[2].body["RequestItems"]["tasks"],
+        json!([{"PutRequest": {"Item": {"id": {"N": "2"}}}}])
+    );
+}
+
+#[test]
+fn unprocessed_items_are_returned_once_the_retries_are_spent() {
+    let unprocessed =
+        json!({"UnprocessedItems": {"tasks": [{"DeleteRequest": {"Key": {"id": {"N": "1"}}}}]}});
+    let mock = Mock::new(move |_| (200, unprocessed.clone()));
+    let client = mock.client();
+
+    let output = block_on(
+        BatchWrite::new(String::from("tasks"), &["id"])
+            .delete(&TaskKey { id: 1 })
+            .retry_policy(no_delay(2))
+            .send(&client),
+    )
+    .unwrap();
+
+    assert_eq!(output.retries, 2);
+    assert_eq!(output.unprocessed_items.len(), 1);
+    assert_eq!(mock.requests().len(), 3);
+}
+
+#[test]
+fn other_errors_are_not_retried() {
+    let mock = Mock::default();
+    mock.respond_with(
+        400,
+        json!({"__type": "com.amazonaws.dynamodb.v20120810#ResourceNotFoundException", "message": "no table"}),
+    );
+    let client = mock.client();
+
+    let error = block_on(
+        BatchWrite::new(String::from("tasks"), &["id"])
+            .put(&task(1))
+            .send(&client),
+    )
+    .unwrap_err();
+
+    assert!(matches!(
+        error,
+        RequestError::Rusoto(rusoto_core::RusotoError::Service(
+            BatchWriteItemError::ResourceNotFound(_)
+        ))
+    ));
+}
+
+#[test]
+fn delays_grow_exponentially_with_jitter() {
+    let policy = RetryPolicy {
+        max_retries: 10,
+        base_delay: Duration::from_millis(100),
+        max_delay: Duration::from_secs(1),
+    };
+    for retry in 0..10 {
+        let cap = (Duration::from_millis(100) * 2u32.pow(retry)).min(Duration::from_secs(1));
+        assert!(policy.delay(retry) <= cap);
+    }
+    let delays: Vec<Duration> = (0..20).map(|_| policy.delay(5)).collect();
+    assert!(delays.iter().any(|delay| *delay != delays[0]));
+}
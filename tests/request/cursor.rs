@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+
+use rusoto_dynamodb::AttributeValue;
+use serde_dynamodb::{decode_cursor, encode_cursor, CursorKey};
+
+const CONTEXT: &str = "tasks:project = p1";
+
+fn last_evaluated_key() -> HashMap<String, AttributeValue> {
+    let mut key = HashMap::new();
+    key.insert(
+        String::from("project"),
+        AttributeValue {
+            s: Some(String::from("p1")),
+            ..Default::default()
+        },
+    );
+    key.insert(
+        String::from("id"),
+        AttributeValue {
+            n: Some(String::from("42")),
+            ..Default::default()
+        },
+    );
+    key
+}
+
+#[test]
+fn can_decode_a_signed_cursor() {
+    let key = CursorKey::new(b"secret");
+    let cursor = encode_cursor(&last_evaluated_key(), CONTEXT, &key).unwrap();
+
+    assert!(cursor
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+    assert_eq!(
+        decode_cursor(&cursor, CONTEXT, &key, &["project", "id"]).unwrap(),
+        last_evaluated_key()
+    );
+    assert!(decode_cursor(
+        &cursor,
+        CONTEXT,
+        &CursorKey::new(b"other"),
+        &["project", "id"]
+    )
+    .is_err());
+}
+
+#[test]
+fn can_decode_an_encrypted_cursor() {
+    let key = CursorKey::new(b"secret").with_encryption([7; 32]);
+    let cursor = encode_cursor(&last_evaluated_key(), CONTEXT, &key).unwrap();
+
+    assert_eq!(
+        decode_cursor(&cursor, CONTEXT, &key, &["project", "id"]).unwrap(),
+        last_evaluated_key()
+    );
+    assert!(decode_cursor(
+        &cursor,
+        CONTEXT,
+        &CursorKey::new(b"secret"),
+        &["project", "id"]
+    )
+    .is_err());
+    assert_ne!(
+        cursor,
+        encode_cursor(&last_evaluated_key(), CONTEXT, &key).unwrap(),
+        "a new nonce is used for each cursor"
+    );
+}
+
+#[test]
+fn forged_cursors_are_rejected() {
+    let key = CursorKey::new(b"secret");
+    let cursor = encode_cursor(&last_evaluated_key(), CONTEXT, &key).unwrap();
+
+    let mut tampered = cursor.clone().into_bytes();
+    let middle = tampered.len() / 2;
+    tampered[middle] = if tampered[middle] == b'A' { b'B' } else { b'A' };
+    let tampered = String::from_utf8(tampered).unwrap();
+    let error = decode_cursor(&tampered, CONTEXT, &key, &["project", "id"]).unwrap_err();
+    assert_eq!(error.message, "invalid cursor");
+
+    assert!(decode_cursor("not a cursor", CONTEXT, &key, &["project", "id"]).is_err());
+    assert!(decode_cursor("", CONTEXT, &key, &["project", "id"]).is_err());
+}
+
+#[test]
+fn cursors_are_bound_to_their_context() {
+    for key in [
+        CursorKey::new(b"secret"),
+        CursorKey::new(b"secret").with_encryption([7; 32]),
+    ] {
+        let cursor = encode_cursor(&last_evaluated_key(), CONTEXT, &key).unwrap();
+        let error =
+            decode_cursor(&cursor, "tasks:project = p2", &key, &["project", "id"]).unwrap_err();
+        assert_eq!(error.message, "invalid cursor");
+    }
+}
+
+#[test]
+fn cursors_must_match_the_key_schema() {
+    let key = CursorKey::new(b"secret");
+    let cursor = encode_cursor(&last_evaluated_key(), CONTEXT, &key).unwrap();
+    assert!(decode_cursor(&cursor, CONTEXT, &key, &["project"]).is_err());
+    assert!(decode_cursor(&cursor, CONTEXT, &key, &["project", "sk"]).is_err());
+
+    let mut not_a_key = last_evaluated_key();
+    not_a_key.insert(
+        String::from("id"),
+        AttributeValue {
+            l: Some(vec![]),
+            ..Default::default()
+        },
+    );
+    let cursor = encode_cursor(&not_a_key, CONTEXT, &key).unwrap();
+    assert!(decode_cursor(&cursor, CONTEXT, &key, &["project", "id"]).is_err());
+}
@@ -0,0 +1,15 @@
+//! The requests sent to DynamoDB, checked against a mock client.
+
+#![cfg(feature = "rusoto_dynamodb")]
+
+mod mock;
+mod task;
+
+mod batch_get;
+mod batch_write;
+#[cfg(feature = "cursor")]
+mod cursor;
+mod paginator;
+mod parallel_scan;
+mod table;
+mod transaction;
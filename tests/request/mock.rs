@@ -0,0 +1,98 @@
+//! A `DynamoDbClient` answering requests with canned responses.
+
+#![allow(dead_code)]
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use rusoto_core::credential::StaticProvider;
+use rusoto_core::request::{DispatchSignedRequestFuture, HttpResponse};
+use rusoto_core::signature::{SignedRequest, SignedRequestPayload};
+use rusoto_core::{DispatchSignedRequest, Region};
+use rusoto_dynamodb::DynamoDbClient;
+
+/// A request sent to the mock, with its operation and its JSON body.
+#[derive(Clone, Debug)]
+pub struct Request {
+    pub operation: String,
+    pub body: serde_json::Value,
+}
+
+type Handler = dyn Fn(&Request) -> (u16, serde_json::Value) + Send + Sync;
+
+#[derive(Clone, Default)]
+pub struct Mock {
+    responses: Arc<Mutex<VecDeque<(u16, serde_json::Value)>>>,
+    handler: Option<Arc<Handler>>,
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+impl Mock {
+    /// Answer the requests with `handler`, once the canned responses are used.
+    pub fn new<F>(handler: F) -> Self
+    where
+        F: Fn(&Request) -> (u16, serde_json::Value) + Send + Sync + 'static,
+    {
+        Mock {
+            handler: Some(Arc::new(handler)),
+            ..Default::default()
+        }
+    }
+
+    /// Answer the next request with `body`.
+    pub fn respond(&self, body: serde_json::Value) -> &Self {
+        self.respond_with(200, body)
+    }
+
+    /// Answer the next request with an error.
+    pub fn respond_with(&self, status: u16, body: serde_json::Value) -> &Self {
+        self.responses.lock().unwrap().push_back((status, body));
+        self
+    }
+
+    /// The requests sent until now.
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    pub fn client(&self) -> DynamoDbClient {
+        DynamoDbClient::new_with(
+            self.clone(),
+            StaticProvider::new_minimal(String::from("key"), String::from("secret")),
+            Region::UsEast1,
+        )
+    }
+}
+
+impl DispatchSignedRequest for Mock {
+    fn dispatch(&self, request: SignedRequest, _: Option<Duration>) -> DispatchSignedRequestFuture {
+        let operation = request
+            .headers
+            .get("x-amz-target")
+            .and_then(|values| values.first())
+            .map(|value| String::from_utf8_lossy(value).replace("DynamoDB_20120810.", ""))
+            .unwrap_or_default();
+        let body = match &request.payload {
+            Some(SignedRequestPayload::Buffer(bytes)) => {
+                serde_json::from_slice(bytes).unwrap_or(serde_json::Value::Null)
+            }
+            _ => serde_json::Value::Null,
+        };
+        let request = Request { operation, body };
+        let response = self.responses.lock().unwrap().pop_front();
+        let (status, body) = match (response, &self.handler) {
+            (Some(response), _) => response,
+            (None, Some(handler)) => handler(&request),
+            (None, None) => panic!("no response left for the request"),
+        };
+        self.requests.lock().unwrap().push(request);
+        Box::pin(async move {
+            Ok(HttpResponse {
+                status: http::StatusCode::from_u16(status).unwrap(),
+                body: serde_json::to_vec(&body).unwrap().into(),
+                headers: Default::default(),
+            })
+        })
+    }
+}
@@ -0,0 +1,84 @@
+use futures::executor::block_on;
+use futures::StreamExt;
+use rusoto_dynamodb::{QueryInput, ScanInput};
+use serde_dynamodb::{Paginator, RequestError};
+use serde_json::json;
+
+use crate::mock::Mock;
+use crate::task::{item, Task, TaskKey};
+
+#[test]
+fn can_follow_the_last_evaluated_key() {
+    let mock = Mock::default();
+    mock.respond(json!({"Items": [item(1), item(2)], "LastEvaluatedKey": {"id": {"N": "2"}}}))
+        .respond(json!({"Items": [], "LastEvaluatedKey": {"id": {"N": "2"}}}))
+        .respond(json!({"Items": [item(3)]}));
+    let client = mock.client();
+    let input = QueryInput {
+        table_name: String::from("tasks"),
+        ..Default::default()
+    };
+
+    let mut tasks = Paginator::<Task, _>::query(&client, input);
+    let ids: Vec<u32> = block_on((&mut tasks).map(|task| task.unwrap().id).collect());
+
+    assert_eq!(ids, vec![1, 2, 3]);
+    assert_eq!(tasks.last_key::<TaskKey>().unwrap(), None);
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests[0].operation, "Query");
+    assert_eq!(requests[0].body.get("ExclusiveStartKey"), None);
+    assert_eq!(
+        requests[1].body["ExclusiveStartKey"],
+        json!({"id": {"N": "2"}})
+    );
+}
+
+#[test]
+fn can_stop_after_some_items() {
+    let mock = Mock::default();
+    mock.respond(json!({"Items": [item(1), item(2)], "LastEvaluatedKey": {"id": {"N": "2"}}}))
+        .respond(json!({"Items": [item(3)], "LastEvaluatedKey": {"id": {"N": "3"}}}));
+    let client = mock.client();
+    let input = ScanInput {
+        table_name: String::from("tasks"),
+        ..Default::default()
+    };
+
+    let mut tasks = Paginator::<Task, _>::scan(&client, input)
+        .page_size(2)
+        .max_items(3);
+    let ids: Vec<u32> = block_on((&mut tasks).map(|task| task.unwrap().id).collect());
+
+    assert_eq!(ids, vec![1, 2, 3]);
+    assert_eq!(
+        tasks.last_key::<TaskKey>().unwrap(),
+        Some(TaskKey { id: 3 })
+    );
+    let requests = mock.requests();
+    assert_eq!(requests[0].operation, "Scan");
+    assert_eq!(requests[0].body["Limit"], json!(2));
+    assert_eq!(requests[1].body["Limit"], json!(1));
+}
+
+#[test]
+fn bad_items_and_failed_requests_are_errors() {
+    let mock = Mock::default();
+    mock.respond(json!({"Items": [item(1), {"id": {"S": "two"}}], "LastEvaluatedKey": {"id": {"N": "2"}}}))
+        .respond_with(
+            400,
+            json!({"__type": "com.amazonaws.dynamodb.v20120810#ResourceNotFoundException", "message": "no table"}),
+        );
+    let client = mock.client();
+    let input = QueryInput {
+        table_name: String::from("tasks"),
+        ..Default::default()
+    };
+
+    let results: Vec<_> = block_on(Paginator::<Task, _>::query(&client, input).collect());
+
+    assert_eq!(results.len(), 3);
+    assert!(results[0].is_ok());
+    assert!(matches!(results[1], Err(RequestError::Serde(_))));
+    assert!(matches!(results[2], Err(RequestError::Rusoto(_))));
+}
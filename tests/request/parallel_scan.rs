@@ -0,0 +1,112 @@
+use futures::executor::block_on;
+use futures::StreamExt;
+use rusoto_dynamodb::ScanInput;
+use serde::{Deserialize, Serialize};
+use serde_dynamodb::{ParallelScan, SegmentCheckpoint};
+use serde_json::json;
+
+use crate::mock::{Mock, Request};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Task {
+    id: i64,
+}
+
+/// Each segment has two pages of two items, the ids being `segment * 10 + n`.
+fn segments(request: &Request) -> (u16, serde_json::Value) {
+    let segment = request.body["Segment"].as_i64().unwrap();
+    match request.body.get("ExclusiveStartKey") {
+        None => (
+            200,
+            json!({
+                "Items": [{"id": {"N": (segment * 10).to_string()}}, {"id": {"N": (segment * 10 + 1).to_string()}}],
+                "LastEvaluatedKey": {"id": {"N": (segment * 10 + 1).to_string()}},
+            }),
+        ),
+        Some(_) => (
+            200,
+            json!({"Items": [{"id": {"N": (segment * 10 + 2).to_string()}}, {"id": {"N": (segment * 10 + 3).to_string()}}]}),
+        ),
+    }
+}
+
+fn input() -> ScanInput {
+    ScanInput {
+        table_name: String::from("tasks"),
+        ..Default::default()
+    }
+}
+
+fn sorted_ids(scan: ParallelScan<Task>) -> Vec<i64> {
+    let mut ids: Vec<i64> = block_on(scan.map(|task| task.unwrap().id).collect());
+    ids.sort_unstable();
+    ids
+}
+
+#[test]
+fn can_scan_all_segments() {
+    let mock = Mock::new(segments);
+    let client = mock.client();
+
+    let ids = sorted_ids(ParallelScan::new(&client, input(), 3));
+
+    assert_eq!(ids, vec![0, 1, 2, 3, 10, 11, 12, 13, 20, 21, 22, 23]);
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 6);
+    assert!(requests
+        .iter()
+        .all(|request| request.body["TotalSegments"] == json!(3)));
+    let first_segments: Vec<_> = requests[..3]
+        .iter()
+        .map(|request| request.body["Segment"].as_i64().unwrap())
+        .collect();
+    assert_eq!(first_segments, vec![0, 1, 2]);
+}
+
+#[test]
+fn can_cap_concurrency() {
+    let mock = Mock::new(segments);
+    let client = mock.client();
+
+    let ids = sorted_ids(ParallelScan::new(&client, input(), 2).max_concurrency(1));
+
+    assert_eq!(ids, vec![0, 1, 2, 3, 10, 11, 12, 13]);
+    let segments: Vec<_> = mock
+        .requests()
+        .iter()
+        .map(|request| request.body["Segment"].as_i64().unwrap())
+        .collect();
+    assert_eq!(segments, vec![0, 0, 1, 1]);
+}
+
+#[test]
+fn can_resume_from_checkpoints() {
+    let mock = Mock::new(segments);
+    let client = mock.client();
+
+    let mut scan = ParallelScan::<Task>::new(&client, input(), 2).max_concurrency(1);
+    let first: Vec<i64> = block_on((&mut scan).take(3).map(|task| task.unwrap().id).collect());
+    assert_eq!(first, vec![0, 1, 2]);
+
+    let checkpoints = scan.checkpoints();
+    assert_eq!(
+        checkpoints,
+        vec![
+            SegmentCheckpoint {
+                segment: 0,
+                exclusive_start_key: Some(serde_dynamodb::to_hashmap(&Task { id: 1 }).unwrap()),
+                done: false,
+            },
+            SegmentCheckpoint {
+                segment: 1,
+                exclusive_start_key: None,
+                done: false,
+            },
+        ]
+    );
+
+    let mut done = checkpoints;
+    done[0].done = true;
+    let ids = sorted_ids(ParallelScan::resume(&client, input(), done));
+    assert_eq!(ids, vec![10, 11, 12, 13]);
+}
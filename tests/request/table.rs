@@ -0,0 +1,353 @@
+use std::time::{Duration, SystemTime};
+
+use futures::executor::block_on;
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use serde_dynamodb::expression::{Condition, ExpressionAttributes, Update};
+use serde_dynamodb::{RequestError, Table, TableError};
+use serde_dynamodb_derive::DynamoItem;
+use serde_json::json;
+
+use crate::mock::Mock;
+use crate::task::{item, task, Task, TaskKey};
+
+#[test]
+fn can_get_an_item_or_not_find_it() {
+    let mock = Mock::default();
+    mock.respond(json!({"Item": item(1)})).respond(json!({}));
+    let client = mock.client();
+    let tasks = Table::<Task, _>::new(&client);
+
+    assert_eq!(block_on(tasks.get(&TaskKey { id: 1 })).unwrap(), task(1));
+    assert!(matches!(
+        block_on(tasks.get(&TaskKey { id: 2 })),
+        Err(TableError::NotFound)
+    ));
+
+    let requests = mock.requests();
+    assert_eq!(requests[0].operation, "GetItem");
+    assert_eq!(requests[0].body["TableName"], "tasks");
+    assert_eq!(requests[0].body["Key"], json!({"id": {"N": "1"}}));
+}
+
+#[test]
+fn put_if_absent_fails_when_the_item_exists() {
+    let mock = Mock::default();
+    mock.respond(json!({})).respond_with(
+        400,
+        json!({"__type": "com.amazonaws.dynamodb.v20120810#ConditionalCheckFailedException", "message": "exists"}),
+    );
+    let client = mock.client();
+    let tasks = Table::<Task, _>::with_table_name(&client, String::from("tasks-test"));
+
+    block_on(tasks.put(&task(1))).unwrap();
+    assert!(matches!(
+        block_on(tasks.put_if_absent(&task(1))),
+        Err(TableError::ConditionalCheckFailed)
+    ));
+
+    let requests = mock.requests();
+    assert_eq!(requests[0].operation, "PutItem");
+    assert_eq!(requests[0].body["TableName"], "tasks-test");
+    assert!(requests[0].body.get("ConditionExpression").is_none());
+    assert_eq!(
+        requests[1].body["ConditionExpression"],
+        "attribute_not_exists (id)"
+    );
+}
+
+#[test]
+fn update_and_delete_return_the_item() {
+    let mock = Mock::default();
+    mock.respond(json!({"Attributes": {"id": {"N": "1"}, "title": {"S": "second"}}}))
+        .respond_with(
+            400,
+            json!({"__type": "com.amazonaws.dynamodb.v20120810#ConditionalCheckFailedException", "message": "missing"}),
+        )
+        .respond(json!({"Attributes": {"id": {"N": "1"}, "title": {"S": "second"}}}))
+        .respond(json!({}));
+    let client = mock.client();
+    let tasks = Table::<Task, _>::new(&client);
+    let before = task(1);
+    let after = Task {
+        title: String::from("second"),
+        ..before.clone()
+    };
+    let update = || Update::between(&before, &after, &["id"]).unwrap();
+
+    assert_eq!(
+        block_on(tasks.update(&TaskKey { id: 1 }, update())).unwrap(),
+        after
+    );
+    assert!(matches!(
+        block_on(tasks.update(&TaskKey { id: 2 }, update())),
+        Err(TableError::NotFound)
+    ));
+    assert_eq!(block_on(tasks.delete(&TaskKey { id: 1 })).unwrap(), after);
+    assert!(matches!(
+        block_on(tasks.delete(&TaskKey { id: 1 })),
+        Err(TableError::NotFound)
+    ));
+
+    let requests = mock.requests();
+    assert_eq!(requests[0].operation, "UpdateItem");
+    assert_eq!(requests[0].body["UpdateExpression"], "SET title = :v0");
+    assert_eq!(
+        requests[0].body["ConditionExpression"],
+        "attribute_exists (id)"
+    );
+    assert_eq!(requests[0].body["ReturnValues"], "ALL_NEW");
+    assert_eq!(requests[2].operation, "DeleteItem");
+    assert_eq!(requests[2].body["ReturnValues"], "ALL_OLD");
+}
+
+#[test]
+fn can_query_and_scan() {
+    let mock = Mock::default();
+    mock.respond(json!({"Items": [item(1)]}))
+        .respond(json!({"Items": [item(1), item(2)]}));
+    let client = mock.client();
+    let tasks = Table::<Task, _>::new(&client);
+
+    let found: Vec<Task> = block_on(
+        tasks
+            .query(Condition::eq("id", &1), None)
+            .unwrap()
+            .try_collect(),
+    )
+    .unwrap();
+    assert_eq!(found.len(), 1);
+    let all: Vec<Task> = block_on(tasks.scan().try_collect()).unwrap();
+    assert_eq!(all.len(), 2);
+
+    let requests = mock.requests();
+    assert_eq!(requests[0].operation, "Query");
+    assert_eq!(requests[0].body["KeyConditionExpression"], "id = :v0");
+    assert_eq!(requests[1].operation, "Scan");
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, DynamoItem)]
+#[dynamodb(table = "accounts")]
+struct Account {
+    #[dynamodb(partition_key)]
+    id: String,
+    balance: u64,
+    #[dynamodb(version)]
+    version: u64,
+}
+
+#[test]
+fn puts_check_and_increment_the_version() {
+    let mock = Mock::default();
+    mock.respond(json!({})).respond(json!({})).respond_with(
+        400,
+        json!({"__type": "com.amazonaws.dynamodb.v20120810#ConditionalCheckFailedException", "message": "version"}),
+    );
+    let client = mock.client();
+    let accounts = Table::<Account, _>::new(&client);
+    let account = Account {
+        id: String::from("a1"),
+        balance: 10,
+        version: 0,
+    };
+
+    let written = block_on(accounts.put(&account)).unwrap();
+    assert_eq!(written.version, 1);
+    let written = block_on(accounts.put(&written)).unwrap();
+    assert_eq!(written.version, 2);
+    assert!(matches!(
+        block_on(accounts.put(&account)),
+        Err(TableError::ConcurrentModification)
+    ));
+
+    let requests = mock.requests();
+    assert_eq!(
+        requests[0].body["ConditionExpression"],
+        "attribute_not_exists (version)"
+    );
+    assert_eq!(requests[0].body["Item"]["version"], json!({"N": "1"}));
+    assert_eq!(requests[1].body["ConditionExpression"], "version = :v0");
+    assert_eq!(
+        requests[1].body["ExpressionAttributeValues"],
+        json!({":v0": {"N": "1"}})
+    );
+    assert_eq!(requests[1].body["Item"]["version"], json!({"N": "2"}));
+}
+
+#[test]
+fn updates_check_and_increment_the_version() {
+    let mock = Mock::default();
+    mock.respond(json!({"Attributes": {"id": {"S": "a1"}, "balance": {"N": "5"}, "version": {"N": "4"}}}))
+        .respond_with(
+            400,
+            json!({"__type": "com.amazonaws.dynamodb.v20120810#ConditionalCheckFailedException", "message": "version"}),
+        )
+        ;
+    let client = mock.client();
+    let accounts = Table::<Account, _>::new(&client);
+    let before = Account {
+        id: String::from("a1"),
+        balance: 10,
+        version: 3,
+    };
+    let after = Account {
+        balance: 5,
+        ..before.clone()
+    };
+
+    let updated = block_on(accounts.update_changes(&before, &after)).unwrap();
+    assert_eq!(updated.version, 4);
+    assert!(matches!(
+        block_on(accounts.update_changes(&before, &after)),
+        Err(TableError::ConcurrentModification)
+    ));
+    let update = Update::between(&before, &after, &["id"]).unwrap();
+    assert!(matches!(
+        block_on(accounts.update(
+            &AccountKey {
+                id: String::from("a1"),
+            },
+            update,
+        )),
+        Err(TableError::Request(RequestError::Serde(_)))
+    ));
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 2);
+    let body = &requests[0].body;
+    assert_eq!(body["Key"], json!({"id": {"S": "a1"}}));
+    assert_eq!(body["UpdateExpression"], "SET balance = :v0, version = :v1");
+    assert_eq!(
+        body["ConditionExpression"],
+        "attribute_exists (id) AND version = :v2"
+    );
+    assert_eq!(body["ExpressionAttributeValues"][":v1"], json!({"N": "4"}));
+    assert_eq!(body["ExpressionAttributeValues"][":v2"], json!({"N": "3"}));
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, DynamoItem)]
+#[dynamodb(table = "sessions")]
+struct Session {
+    #[dynamodb(partition_key)]
+    id: String,
+    #[dynamodb(created_at)]
+    #[serde(with = "serde_dynamodb::timestamp::epoch_seconds::option")]
+    created: Option<SystemTime>,
+    #[dynamodb(updated_at)]
+    #[serde(with = "serde_dynamodb::timestamp::epoch_seconds")]
+    updated: SystemTime,
+    #[dynamodb(ttl)]
+    #[serde(with = "serde_dynamodb::timestamp::epoch_seconds")]
+    expires: Duration,
+}
+
+#[test]
+fn timestamps_are_set_on_writes() {
+    let mock = Mock::default();
+    mock.respond(json!({})).respond(json!({"Attributes": {
+        "id": {"S": "s1"},
+        "created": {"N": "1"},
+        "updated": {"N": "2"},
+        "expires": {"N": "3"}
+    }}));
+    let client = mock.client();
+    let sessions = Table::<Session, _>::new(&client);
+    let session = Session {
+        id: String::from("s1"),
+        created: None,
+        updated: SystemTime::UNIX_EPOCH,
+        expires: Duration::from_secs(3600),
+    };
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    let written = block_on(sessions.put(&session)).unwrap();
+    assert!(written.created.is_some());
+    assert!(written.updated > SystemTime::UNIX_EPOCH);
+    let update = Update::between(&session, &session, &["id"]).unwrap();
+    block_on(sessions.update(
+        &SessionKey {
+            id: String::from("s1"),
+        },
+        update,
+    ))
+    .unwrap();
+
+    let requests = mock.requests();
+    let item = &requests[0].body["Item"];
+    let seconds = |value: &serde_json::Value| value["N"].as_str().unwrap().parse::<u64>().unwrap();
+    assert!(seconds(&item["created"]) >= now);
+    assert!(seconds(&item["updated"]) >= now);
+    assert!(seconds(&item["expires"]) >= now + 3600);
+    assert_eq!(
+        requests[1].body["UpdateExpression"],
+        "SET #n1 = :v1, #n0 = if_not_exists(#n0, :v0)"
+    );
+    assert_eq!(
+        requests[1].body["ExpressionAttributeNames"],
+        json!({"#n0": "created", "#n1": "updated"})
+    );
+}
+
+#[test]
+fn timestamps_written_by_the_update_are_kept() {
+    let mock = Mock::default();
+    mock.respond(json!({"Attributes": {
+        "id": {"S": "s1"},
+        "created": {"N": "1"},
+        "updated": {"N": "5"},
+        "expires": {"N": "3"}
+    }}));
+    let client = mock.client();
+    let sessions = Table::<Session, _>::new(&client);
+    let mut attributes = ExpressionAttributes::new();
+    attributes
+        .names
+        .insert(String::from("#u"), String::from("updated"));
+    attributes.values.insert(
+        String::from(":u"),
+        serde_dynamodb::to_attribute_value(&5).unwrap(),
+    );
+    let update = Update {
+        update_expression: Some(String::from("SET notes = list_append(notes, :u), #u = :u")),
+        attributes,
+    };
+
+    block_on(sessions.update(
+        &SessionKey {
+            id: String::from("s1"),
+        },
+        update,
+    ))
+    .unwrap();
+
+    assert_eq!(
+        mock.requests()[0].body["UpdateExpression"],
+        "SET #n1 = if_not_exists(#n1, :v1), notes = list_append(notes, :u), #u = :u"
+    );
+}
+
+#[test]
+fn a_time_to_live_must_be_a_number() {
+    #[derive(Serialize, Deserialize, DynamoItem)]
+    #[dynamodb(table = "sessions")]
+    struct Session {
+        #[dynamodb(partition_key)]
+        id: String,
+        #[dynamodb(ttl)]
+        expires: String,
+    }
+
+    let client = Mock::default().client();
+    let sessions = Table::<Session, _>::new(&client);
+    let session = Session {
+        id: String::from("s1"),
+        expires: String::from("2020-01-01T00:00:00Z"),
+    };
+    assert!(matches!(
+        block_on(sessions.put(&session)),
+        Err(TableError::Request(_))
+    ));
+}
@@ -0,0 +1,36 @@
+//! The item most requests are tested with.
+
+use std::hash::{Hash, Hasher};
+
+use serde::{Deserialize, Serialize};
+use serde_dynamodb_derive::DynamoItem;
+use serde_json::json;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, DynamoItem)]
+#[dynamodb(table = "tasks")]
+pub struct Task {
+    #[dynamodb(partition_key)]
+    pub id: u32,
+    pub title: String,
+}
+
+// batch_get matches items to their keys in a HashMap
+impl Eq for TaskKey {}
+
+impl Hash for TaskKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+pub fn task(id: u32) -> Task {
+    Task {
+        id,
+        title: format!("task {}", id),
+    }
+}
+
+/// The task `id`, as returned by DynamoDB.
+pub fn item(id: u32) -> serde_json::Value {
+    json!({"id": {"N": id.to_string()}, "title": {"S": format!("task {}", id)}})
+}
@@ -0,0 +1,165 @@
+use futures::executor::block_on;
+use rusoto_dynamodb::CancellationReason;
+use serde::{Deserialize, Serialize};
+use serde_dynamodb::expression::{Condition, Update};
+use serde_dynamodb::{Transaction, TransactionError};
+use serde_dynamodb_derive::DynamoItem;
+use serde_json::json;
+
+use crate::mock::Mock;
+
+#[derive(Serialize, Deserialize, DynamoItem)]
+#[dynamodb(table = "orders")]
+struct Order {
+    #[dynamodb(partition_key)]
+    id: String,
+    total: u64,
+    note: String,
+}
+
+#[derive(Clone, Serialize, Deserialize, DynamoItem)]
+#[dynamodb(table = "accounts")]
+struct Account {
+    #[dynamodb(partition_key)]
+    id: String,
+    balance: u64,
+}
+
+fn order(id: u32) -> Order {
+    Order {
+        id: format!("o{}", id),
+        total: 10,
+        note: String::new(),
+    }
+}
+
+fn account(balance: u64) -> Account {
+    Account {
+        id: String::from("a1"),
+        balance,
+    }
+}
+
+#[test]
+fn can_build_a_transaction() {
+    let before = account(100);
+    let after = account(90);
+    let input = Transaction::new()
+        .put(&order(1))
+        .condition(Condition::attribute_not_exists("id"))
+        .update(&after, Update::between(&before, &after, &["id"]).unwrap())
+        .condition(Condition::eq("balance", &100))
+        .delete::<Order>(&OrderKey {
+            id: String::from("o2"),
+        })
+        .check::<Account>(
+            &AccountKey {
+                id: String::from("a2"),
+            },
+            Condition::attribute_exists("id"),
+        )
+        .build()
+        .unwrap();
+
+    let items = &input.transact_items;
+    assert_eq!(items.len(), 4);
+
+    let put = items[0].put.as_ref().unwrap();
+    assert_eq!(put.table_name, "orders");
+    assert_eq!(
+        put.condition_expression.as_deref(),
+        Some("attribute_not_exists (id)")
+    );
+
+    let update = items[1].update.as_ref().unwrap();
+    assert_eq!(update.table_name, "accounts");
+    assert_eq!(update.key.len(), 1);
+    assert_eq!(update.update_expression, "SET balance = :v0");
+    assert_eq!(
+        update.condition_expression.as_deref(),
+        Some("balance = :v1")
+    );
+    let values = update.expression_attribute_values.as_ref().unwrap();
+    assert_eq!(values[":v0"].n.as_deref(), Some("90"));
+    assert_eq!(values[":v1"].n.as_deref(), Some("100"));
+
+    let delete = items[2].delete.as_ref().unwrap();
+    assert_eq!(delete.key["id"].s.as_deref(), Some("o2"));
+    assert!(delete.condition_expression.is_none());
+
+    let check = items[3].condition_check.as_ref().unwrap();
+    assert_eq!(check.table_name, "accounts");
+    assert_eq!(check.condition_expression, "attribute_exists (id)");
+}
+
+#[test]
+fn limits_are_enforced_before_sending() {
+    let too_many = (0..101).fold(Transaction::new(), |tx, id| tx.put(&order(id)));
+    assert!(too_many.build().is_err());
+
+    let big = |id| Order {
+        note: "x".repeat(400 * 1024 - 100),
+        ..order(id)
+    };
+    let too_big = (0..11).fold(Transaction::new(), |tx, id| tx.put(&big(id)));
+    assert!(too_big.build().is_err());
+    let fits = (0..10).fold(Transaction::new(), |tx, id| tx.put(&big(id)));
+    assert!(fits.build().is_ok());
+
+    let misplaced = Transaction::new()
+        .condition(Condition::attribute_not_exists("id"))
+        .put(&order(1));
+    assert!(misplaced.build().is_err());
+
+    let before = account(1);
+    let nothing =
+        Transaction::new().update(&before, Update::between(&before, &before, &["id"]).unwrap());
+    assert!(nothing.build().is_err());
+}
+
+#[test]
+fn cancellation_reasons_are_mapped_to_operations() {
+    let mock = Mock::default();
+    mock.respond_with(
+        400,
+        json!({
+            "__type": "com.amazonaws.dynamodb.v20120810#TransactionCanceledException",
+            "message": "Transaction cancelled, please refer cancellation reasons for specific reasons [None, ConditionalCheckFailed, None]"
+        }),
+    );
+    let client = mock.client();
+
+    let error = block_on(
+        Transaction::new()
+            .put(&order(1))
+            .put(&order(2))
+            .condition(Condition::attribute_not_exists("id"))
+            .put(&order(3))
+            .send(&client),
+    )
+    .unwrap_err();
+
+    match error {
+        TransactionError::Canceled(reasons) => {
+            let reason = |code: &str| CancellationReason {
+                code: Some(String::from(code)),
+                ..Default::default()
+            };
+            assert_eq!(
+                reasons,
+                vec![
+                    reason("None"),
+                    reason("ConditionalCheckFailed"),
+                    reason("None")
+                ]
+            );
+        }
+        error => panic!("unexpected error {:?}", error),
+    }
+    let requests = mock.requests();
+    assert_eq!(requests[0].operation, "TransactWriteItems");
+    assert_eq!(
+        requests[0].body["TransactItems"].as_array().unwrap().len(),
+        3
+    );
+}
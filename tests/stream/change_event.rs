@@ -0,0 +1,97 @@
+use rusoto_dynamodbstreams::{Record, StreamRecord};
+use serde::{Deserialize, Serialize};
+use serde_dynamodb::streams::{ChangeEvent, EventName, StreamViewType};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Key {
+    id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Task {
+    id: String,
+    done: bool,
+}
+
+fn record(event_name: &str, view_type: &str, old: Option<bool>, new: Option<bool>) -> Record {
+    let image = |done| {
+        serde_dynamodb::streams::to_hashmap(&Task {
+            id: String::from("task"),
+            done,
+        })
+        .unwrap()
+    };
+    Record {
+        event_id: Some(String::from("event")),
+        event_name: Some(String::from(event_name)),
+        dynamodb: Some(StreamRecord {
+            keys: Some(
+                serde_dynamodb::streams::to_hashmap(&Key {
+                    id: String::from("task"),
+                })
+                .unwrap(),
+            ),
+            old_image: old.map(image),
+            new_image: new.map(image),
+            sequence_number: Some(String::from("100")),
+            approximate_creation_date_time: Some(1_600_000_000.0),
+            stream_view_type: Some(String::from(view_type)),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn can_decode_modify_event() {
+    let event: ChangeEvent<Key, Task> = ChangeEvent::from_record(record(
+        "MODIFY",
+        "NEW_AND_OLD_IMAGES",
+        Some(false),
+        Some(true),
+    ))
+    .unwrap();
+
+    assert_eq!(event.event_name, EventName::Modify);
+    assert_eq!(
+        event.keys,
+        Key {
+            id: String::from("task")
+        }
+    );
+    assert_eq!(event.old_image.map(|task| task.done), Some(false));
+    assert_eq!(event.new_image.map(|task| task.done), Some(true));
+    assert_eq!(event.sequence_number.as_deref(), Some("100"));
+    assert_eq!(
+        event.stream_view_type,
+        Some(StreamViewType::NewAndOldImages)
+    );
+}
+
+#[test]
+fn keys_only_stream_has_no_images() {
+    let event: ChangeEvent<Key, Task> =
+        ChangeEvent::from_record(record("INSERT", "KEYS_ONLY", None, None)).unwrap();
+
+    assert_eq!(event.event_name, EventName::Insert);
+    assert!(event.old_image.is_none());
+    assert!(event.new_image.is_none());
+}
+
+#[test]
+fn new_and_old_images_stream_has_only_old_image_on_remove() {
+    let event: ChangeEvent<Key, Task> =
+        ChangeEvent::from_record(record("REMOVE", "NEW_AND_OLD_IMAGES", Some(true), None)).unwrap();
+
+    assert_eq!(event.event_name, EventName::Remove);
+    assert_eq!(event.old_image.map(|task| task.done), Some(true));
+    assert!(event.new_image.is_none());
+}
+
+#[test]
+fn fails_on_missing_expected_image() {
+    let event: Result<ChangeEvent<Key, Task>, _> =
+        ChangeEvent::from_record(record("MODIFY", "NEW_AND_OLD_IMAGES", None, Some(true)));
+
+    assert!(event.is_err());
+}
@@ -0,0 +1,68 @@
+use serde::Deserialize;
+use serde_dynamodb::kinesis::KinesisRecord;
+use serde_dynamodb::streams::EventName;
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Key {
+    artist: String,
+    title: String,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Song {
+    artist: String,
+    title: String,
+    year: u16,
+}
+
+fn record(event_name: &str, user_identity: &str) -> String {
+    format!(
+        r#"{{
+            "awsRegion": "us-east-1",
+            "eventID": "26b1b865-0b3c-4a4a-8b7e-4b8f6b5c6d7e",
+            "eventName": "{}",
+            {}
+            "recordFormat": "application/json",
+            "tableName": "Music",
+            "dynamodb": {{
+                "ApproximateCreationDateTime": 1611357270000,
+                "Keys": {{"artist": {{"S": "Acme Band"}}, "title": {{"S": "Happy Day"}}}},
+                "OldImage": {{
+                    "artist": {{"S": "Acme Band"}},
+                    "title": {{"S": "Happy Day"}},
+                    "year": {{"N": "2020"}}
+                }},
+                "SizeBytes": 123
+            }},
+            "eventSource": "aws:dynamodb"
+        }}"#,
+        event_name, user_identity
+    )
+}
+
+#[test]
+fn can_decode_kinesis_record() {
+    let record = KinesisRecord::from_slice(record("REMOVE", "").as_bytes()).unwrap();
+    assert_eq!(record.table_name.as_deref(), Some("Music"));
+
+    let event = record.into_change_event::<Key, Song>().unwrap();
+    assert_eq!(event.event_name, EventName::Remove);
+    assert_eq!(event.keys.title, "Happy Day");
+    assert_eq!(event.old_image.map(|song| song.year), Some(2020));
+    assert_eq!(event.approximate_creation_date_time, Some(1_611_357_270.0));
+    assert!(!event.removed_by_ttl);
+}
+
+#[test]
+fn flags_ttl_deletions() {
+    let event = serde_dynamodb::kinesis::from_slice::<Key, Song>(
+        record(
+            "REMOVE",
+            r#""userIdentity": {"type": "Service", "principalId": "dynamodb.amazonaws.com"},"#,
+        )
+        .as_bytes(),
+    )
+    .unwrap();
+
+    assert!(event.removed_by_ttl);
+}
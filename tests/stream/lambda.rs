@@ -0,0 +1,123 @@
+use serde::Deserialize;
+use serde_dynamodb::lambda::LambdaEvent;
+use serde_dynamodb::streams::EventName;
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Key {
+    id: i32,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Item {
+    id: i32,
+    message: String,
+    #[serde(with = "as_bytes")]
+    data: Vec<u8>,
+}
+
+mod as_bytes {
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct BytesVisitor;
+        impl<'de> serde::de::Visitor<'de> for BytesVisitor {
+            type Value = Vec<u8>;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("bytes")
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<Vec<u8>, E> {
+                Ok(v.to_vec())
+            }
+        }
+        deserializer.deserialize_bytes(BytesVisitor)
+    }
+}
+
+const PAYLOAD: &str = r#"{
+  "Records": [
+    {
+      "eventID": "c4ca4238a0b923820dcc509a6f75849b",
+      "eventName": "INSERT",
+      "eventVersion": "1.1",
+      "eventSource": "aws:dynamodb",
+      "awsRegion": "us-east-1",
+      "dynamodb": {
+        "ApproximateCreationDateTime": 1428537600,
+        "Keys": {"id": {"N": "101"}},
+        "NewImage": {
+          "message": {"S": "New item!"},
+          "id": {"N": "101"},
+          "data": {"B": "aGVsbG8="}
+        },
+        "SequenceNumber": "4421584500000000017450439091",
+        "SizeBytes": 26,
+        "StreamViewType": "NEW_AND_OLD_IMAGES"
+      },
+      "eventSourceARN": "arn:aws:dynamodb:us-east-1:123456789012:table/ExampleTableWithStream/stream/2015-06-27T00:48:05.899"
+    },
+    {
+      "eventID": "eccbc87e4b5ce2fe28308fd9f2a7baf3",
+      "eventName": "REMOVE",
+      "eventVersion": "1.1",
+      "eventSource": "aws:dynamodb",
+      "awsRegion": "us-east-1",
+      "dynamodb": {
+        "Keys": {"id": {"N": "101"}},
+        "OldImage": {
+          "message": {"S": "This item has changed"},
+          "id": {"N": "101"},
+          "data": {"B": "aGVsbG8="}
+        },
+        "SequenceNumber": "4421584500000000017450439093",
+        "SizeBytes": 38,
+        "StreamViewType": "NEW_AND_OLD_IMAGES"
+      },
+      "eventSourceARN": "arn:aws:dynamodb:us-east-1:123456789012:table/ExampleTableWithStream/stream/2015-06-27T00:48:05.899"
+    }
+  ]
+}"#;
+
+#[test]
+fn can_parse_lambda_event() {
+    let event: LambdaEvent = PAYLOAD.parse().unwrap();
+    assert_eq!(event.records.len(), 2);
+    assert_eq!(event.records[0].aws_region.as_deref(), Some("us-east-1"));
+}
+
+#[test]
+fn can_decode_lambda_event_into_change_events() {
+    let events = serde_dynamodb::lambda::from_str::<Key, Item>(PAYLOAD).unwrap();
+
+    assert_eq!(events[0].event_name, EventName::Insert);
+    assert_eq!(events[0].keys, Key { id: 101 });
+    assert_eq!(
+        events[0].new_image,
+        Some(Item {
+            id: 101,
+            message: String::from("New item!"),
+            data: b"hello".to_vec(),
+        })
+    );
+    assert_eq!(
+        events[0].approximate_creation_date_time,
+        Some(1_428_537_600.0)
+    );
+
+    assert_eq!(events[1].event_name, EventName::Remove);
+    assert!(events[1].new_image.is_none());
+    assert_eq!(
+        events[1]
+            .old_image
+            .as_ref()
+            .map(|item| item.message.as_str()),
+        Some("This item has changed")
+    );
+}
+
+#[test]
+fn fails_on_invalid_payload() {
+    assert!(serde_dynamodb::lambda::from_str::<Key, Item>(r#"{"Records": 1}"#).is_err());
+}
@@ -0,0 +1,11 @@
+//! The records of DynamoDB streams, as received directly, from Lambda or from
+//! Kinesis.
+
+#![cfg(feature = "rusoto_dynamodbstreams")]
+
+mod change_event;
+#[cfg(feature = "kinesis")]
+mod kinesis;
+#[cfg(feature = "lambda")]
+mod lambda;
+mod processor;
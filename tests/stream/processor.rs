@@ -0,0 +1,219 @@
+use std::cell::RefCell;
+
+use rusoto_dynamodbstreams::{AttributeValue, Record, StreamRecord};
+use serde::{Deserialize, Serialize};
+use serde_dynamodb::streams::{BatchItemFailure, BatchProcessor, BatchResponse};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Key {
+    id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Task {
+    id: String,
+    done: bool,
+}
+
+fn record(sequence_number: &str, id: &str, done: Option<bool>) -> Record {
+    let mut new_image = serde_dynamodb::streams::to_hashmap(&Key { id: id.to_owned() }).unwrap();
+    if let Some(done) = done {
+        new_image.insert(
+            String::from("done"),
+            AttributeValue {
+                bool: Some(done),
+                ..Default::default()
+            },
+        );
+    }
+    Record {
+        event_name: Some(String::from("INSERT")),
+        dynamodb: Some(StreamRecord {
+            keys: Some(serde_dynamodb::streams::to_hashmap(&Key { id: id.to_owned() }).unwrap()),
+            new_image: Some(new_image),
+            sequence_number: Some(sequence_number.to_owned()),
+            stream_view_type: Some(String::from("NEW_IMAGE")),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+fn failures(ids: &[&str]) -> BatchResponse {
+    BatchResponse {
+        batch_item_failures: ids
+            .iter()
+            .map(|id| BatchItemFailure {
+                item_identifier: String::from(*id),
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn handles_other_keys_after_a_failure() {
+    let handled = RefCell::new(vec![]);
+    let records = vec![
+        record("1", "a", Some(false)),
+        record("2", "b", Some(true)),
+        record("3", "a", Some(false)),
+        record("4", "b", Some(false)),
+    ];
+
+    let response =
+        BatchProcessor::<Key, Task>::new("id").process(records, |event| -> Result<(), ()> {
+            let task = event.new_image.unwrap();
+            handled.borrow_mut().push(task.id.clone());
+            if task.done {
+                Err(())
+            } else {
+                Ok(())
+            }
+        });
+
+    assert_eq!(response, failures(&["2", "4"]));
+    assert_eq!(*handled.borrow(), vec!["a", "b", "a"]);
+}
+
+#[test]
+fn stops_at_first_failure_per_partition_key() {
+    #[derive(Serialize, Deserialize)]
+    struct OrderKey {
+        customer: String,
+        order: String,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Order {
+        customer: String,
+        order: String,
+        done: bool,
+    }
+
+    let record = |sequence_number: &str, customer: &str, order: &str, done: bool| {
+        let order = Order {
+            customer: customer.to_owned(),
+            order: order.to_owned(),
+            done,
+        };
+        let mut keys = serde_dynamodb::streams::to_hashmap(&order).unwrap();
+        keys.remove("done");
+        Record {
+            event_name: Some(String::from("INSERT")),
+            dynamodb: Some(StreamRecord {
+                keys: Some(keys),
+                new_image: Some(serde_dynamodb::streams::to_hashmap(&order).unwrap()),
+                sequence_number: Some(sequence_number.to_owned()),
+                stream_view_type: Some(String::from("NEW_IMAGE")),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    };
+    let handled = RefCell::new(vec![]);
+    let records = vec![
+        record("1", "c1", "o1", true),
+        record("2", "c1", "o2", false),
+        record("3", "c2", "o1", false),
+    ];
+
+    let response = BatchProcessor::<OrderKey, Order>::new("customer").process(
+        records,
+        |event| -> Result<(), ()> {
+            let order = event.new_image.unwrap();
+            handled.borrow_mut().push(order.order.clone());
+            if order.done {
+                Err(())
+            } else {
+                Ok(())
+            }
+        },
+    );
+
+    assert_eq!(response, failures(&["1", "2"]));
+    assert_eq!(*handled.borrow(), vec!["o1", "o1"]);
+}
+
+#[test]
+fn handles_each_record_without_partition_key_on_its_own() {
+    let handled = RefCell::new(vec![]);
+    let records = vec![
+        record("1", "a", Some(true)),
+        record("2", "b", Some(false)),
+        record("3", "c", Some(false)),
+    ];
+
+    let response =
+        BatchProcessor::<Key, Task>::new("customer").process(records, |event| -> Result<(), ()> {
+            let task = event.new_image.unwrap();
+            handled.borrow_mut().push(task.id.clone());
+            if task.done {
+                Err(())
+            } else {
+                Ok(())
+            }
+        });
+
+    assert_eq!(response, failures(&["1"]));
+    assert_eq!(*handled.borrow(), vec!["a", "b", "c"]);
+}
+
+#[test]
+fn fails_the_batch_without_sequence_number() {
+    let handled = RefCell::new(vec![]);
+    let mut records = vec![
+        record("1", "a", Some(false)),
+        record("2", "b", Some(true)),
+        record("3", "c", Some(false)),
+    ];
+    records[1].dynamodb.as_mut().unwrap().sequence_number = None;
+
+    let response =
+        BatchProcessor::<Key, Task>::new("id").process(records, |event| -> Result<(), ()> {
+            let task = event.new_image.unwrap();
+            handled.borrow_mut().push(task.id.clone());
+            if task.done {
+                Err(())
+            } else {
+                Ok(())
+            }
+        });
+
+    assert_eq!(response, failures(&[""]));
+    assert_eq!(*handled.borrow(), vec!["a", "b"]);
+}
+
+#[test]
+fn reports_undecodable_records_without_dead_letter() {
+    let records = vec![record("1", "a", None), record("2", "b", Some(true))];
+
+    let response =
+        BatchProcessor::<Key, Task>::new("id").process(records, |_| -> Result<(), ()> { Ok(()) });
+
+    assert_eq!(response, failures(&["1"]));
+}
+
+#[test]
+fn sends_undecodable_records_to_dead_letter() {
+    let dead_letters = RefCell::new(vec![]);
+    let records = vec![record("1", "a", None), record("2", "b", Some(true))];
+
+    let response = BatchProcessor::<Key, Task>::new("id")
+        .dead_letter(|record, _| {
+            dead_letters
+                .borrow_mut()
+                .push(record.dynamodb.as_ref().unwrap().sequence_number.clone())
+        })
+        .process(records, |_| -> Result<(), ()> { Ok(()) });
+
+    assert_eq!(response, BatchResponse::default());
+    assert_eq!(*dead_letters.borrow(), vec![Some(String::from("1"))]);
+}
+
+#[test]
+fn response_has_lambda_shape() {
+    assert_eq!(
+        serde_json::to_string(&failures(&["1"])).unwrap(),
+        r#"{"batchItemFailures":[{"itemIdentifier":"1"}]}"#
+    );
+}